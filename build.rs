@@ -4,9 +4,13 @@ use std::process::Command;
 use flate2::read::GzDecoder;
 use tar::Archive;
 use curl::easy::Easy;
+use sha2::{Digest, Sha256};
 use std::io::Write;
 
+const OPENFST_NAME: &str = "openfst-1.6.7";
 const OPENFST_SRC: &str = "https://www.openfst.org/twiki/pub/FST/FstDownload/openfst-1.6.7.tar.gz";
+const OPENFST_SHA256: &str = "e21a486d827cde6a592c8e91721e4540ad01a5ae35a60423cf17be4d716017f7";
+const OPENFST_VENDORED: &str = "vendor/openfst-1.6.7.tar.gz";
 
 fn download<P: AsRef<Path>>(source_url: &str, target_file: P) -> anyhow::Result<()> {    
     let f = fs::File::create(&target_file)?;
@@ -38,28 +42,127 @@ fn extract<P1: AsRef<Path>, P2: AsRef<Path>>(filename: P1, outpath: P2) -> anyho
     Ok(())
 }
 
-fn main() {
-    println!("cargo:rustc-link-lib=dylib=lapack");
+fn verify_checksum<P: AsRef<Path>>(filename: P) -> anyhow::Result<()> {
+    let expected = env::var("OPENFST_SHA256").unwrap_or_else(|_| OPENFST_SHA256.to_string());
 
-    let out_path = PathBuf::from(env::var("OUT_DIR").unwrap());
+    let mut file = fs::File::open(&filename)?;
+    let mut hasher = Sha256::new();
+    io::copy(&mut file, &mut hasher)?;
+    let actual: String = hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect();
 
-    println!("cargo:rerun-if-changed=cbits/vosk.h");
-    println!("cargo:rerun-if-changed=build.rs");
+    if actual.eq_ignore_ascii_case(expected.trim()) {
+        Ok(())
+    } else {
+        Err(anyhow::anyhow!(
+            "Checksum mismatch for {}: expected {}, got {}",
+            filename.as_ref().display(),
+            expected,
+            actual
+        ))
+    }
+}
+
+fn is_offline() -> bool {
+    ["VOSK_OFFLINE", "CARGO_NET_OFFLINE"]
+        .iter()
+        .any(|var| matches!(env::var(var).as_deref(), Ok("1") | Ok("true")))
+}
+
+// Looks for OpenFST sources in this order: an extracted directory given by
+// OPENFST_DIR, a tarball given by OPENFST_TARBALL, the vendored tarball, and
+// only then openfst.org. Tarballs are checked against OPENFST_SHA256.
+fn openfst_source(out_path: &Path) -> anyhow::Result<PathBuf> {
+    if let Some(dir) = env::var_os("OPENFST_DIR") {
+        let dir = PathBuf::from(dir);
+        if !dir.join("src/include/fst/fst.h").exists() {
+            return Err(anyhow::anyhow!("OPENFST_DIR={} is not an OpenFST source tree", dir.display()));
+        }
+
+        return Ok(dir);
+    }
+
+    let openfst_dir = out_path.join(OPENFST_NAME);
+    if openfst_dir.exists() {
+        return Ok(openfst_dir);
+    }
+
+    let vendored = PathBuf::from(env::var("CARGO_MANIFEST_DIR")?).join(OPENFST_VENDORED);
+    let tarball = if let Some(tarball) = env::var_os("OPENFST_TARBALL") {
+        PathBuf::from(tarball)
+    } else if vendored.exists() {
+        vendored
+    } else if is_offline() {
+        return Err(anyhow::anyhow!(
+            "Offline build requested but no OpenFST sources found, set OPENFST_DIR or OPENFST_TARBALL or place {} in {}",
+            OPENFST_NAME,
+            OPENFST_VENDORED
+        ));
+    } else {
+        let openfst_name = out_path.join("openfst.tar.gz");
+        download(OPENFST_SRC, &openfst_name)?;
+        openfst_name
+    };
+
+    verify_checksum(&tarball)?;
+    extract(&tarball, out_path)?;
 
-    let openfst_name = out_path.join("openfst.tar.gz");
-    let openfst_dir = out_path.join("openfst-1.6.7");
     if !openfst_dir.exists() {
-        download(OPENFST_SRC, &openfst_name).unwrap();
-        extract(openfst_name, &out_path).unwrap();
+        return Err(anyhow::anyhow!("{} does not contain {}", tarball.display(), OPENFST_NAME));
+    }
+
+    Ok(openfst_dir)
+}
+
+fn use_system_vosk() -> bool {
+    env::var_os("CARGO_FEATURE_SYSTEM").is_some() || env::var_os("VOSK_LIB_DIR").is_some()
+}
+
+// Links an existing libvosk instead of compiling it. VOSK_LIB_DIR (with
+// VOSK_INCLUDE_DIR, defaulting to VOSK_LIB_DIR/../include) takes precedence
+// over pkg-config. Returns the include dirs for bindgen.
+fn link_system_vosk() -> Vec<PathBuf> {
+    if let Some(lib_dir) = env::var_os("VOSK_LIB_DIR") {
+        let lib_dir = PathBuf::from(lib_dir);
+        let include_dir = env::var_os("VOSK_INCLUDE_DIR")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| lib_dir.join("../include"));
+
+        println!("cargo:rustc-link-search=native={}", lib_dir.display());
+        if env::var_os("VOSK_STATIC").is_some() {
+            println!("cargo:rustc-link-lib=static=vosk");
+            println!("cargo:rustc-link-lib=dylib=stdc++");
+        } else {
+            println!("cargo:rustc-link-lib=dylib=vosk");
+        }
+
+        return vec![include_dir];
     }
 
-    let bindings = bindgen::Builder::default()
+    let library = pkg_config::Config::new()
+        .statik(env::var_os("VOSK_STATIC").is_some())
+        .probe("vosk")
+        .expect("libvosk not found, set VOSK_LIB_DIR or make vosk.pc visible to pkg-config");
+
+    library.include_paths
+}
+
+// Without the `bindgen` feature the checked-in src/bindings.rs is used and
+// libclang is not needed.
+#[cfg(not(feature = "bindgen"))]
+fn generate_bindings(_out_path: &Path, _include_dirs: &[PathBuf]) {}
+
+#[cfg(feature = "bindgen")]
+fn generate_bindings(out_path: &Path, include_dirs: &[PathBuf]) {
+    let mut builder = bindgen::Builder::default()
         .generate_inline_functions(true)
         .derive_default(false)
-        .header("cbits/vosk.h")
-        .clang_arg("-I./resources/vosk-api/src/")
-        .clang_arg("-I./resources/kaldi/src/")
-        .clang_arg(format!("-I{}", openfst_dir.join("src/include").to_string_lossy()))
+        .header("cbits/vosk.h");
+
+    for dir in include_dirs {
+        builder = builder.clang_arg(format!("-I{}", dir.to_string_lossy()));
+    }
+
+    let bindings = builder
         .clang_arg("-std=c++14")
         .clang_arg("-x")
         .clang_arg("c++")
@@ -78,6 +181,56 @@ fn main() {
         .write_to_file(out_path.join("bindings.rs"))
         .expect("Couldn't write bindings!");
 
+    if env::var_os("VOSK_UPDATE_BINDINGS").is_some() {
+        bindings
+            .write_to_file("src/bindings.rs")
+            .expect("Couldn't update src/bindings.rs!");
+    }
+}
+
+fn main() {
+    let out_path = PathBuf::from(env::var("OUT_DIR").unwrap());
+
+    println!("cargo:rerun-if-changed=cbits/vosk.h");
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-env-changed=VOSK_LIB_DIR");
+    println!("cargo:rerun-if-env-changed=VOSK_INCLUDE_DIR");
+    println!("cargo:rerun-if-env-changed=VOSK_STATIC");
+    println!("cargo:rerun-if-env-changed=VOSK_UPDATE_BINDINGS");
+
+    // libvosk is loaded at runtime, nothing to build or link.
+    if env::var_os("CARGO_FEATURE_DYNAMIC").is_some() {
+        return;
+    }
+
+    if use_system_vosk() {
+        let include_dirs = link_system_vosk();
+        generate_bindings(&out_path, &include_dirs);
+    } else {
+        if env::var_os("CARGO_FEATURE_BUNDLED").is_none() {
+            panic!("Either the `bundled` or the `system` feature must be enabled");
+        }
+
+        let openfst_dir = build_bundled(&out_path);
+        generate_bindings(&out_path, &[
+            PathBuf::from("./resources/vosk-api/src/"),
+            PathBuf::from("./resources/kaldi/src/"),
+            openfst_dir.join("src/include"),
+        ]);
+    }
+}
+
+// Compiles OpenFST, Kaldi and Vosk from source. Returns the OpenFST source dir.
+fn build_bundled(out_path: &Path) -> PathBuf {
+    println!("cargo:rustc-link-lib=dylib=lapack");
+    println!("cargo:rerun-if-changed={}", OPENFST_VENDORED);
+    println!("cargo:rerun-if-env-changed=OPENFST_DIR");
+    println!("cargo:rerun-if-env-changed=OPENFST_TARBALL");
+    println!("cargo:rerun-if-env-changed=OPENFST_SHA256");
+    println!("cargo:rerun-if-env-changed=VOSK_OFFLINE");
+
+    let openfst_dir = openfst_source(out_path).unwrap();
+
     cc::Build::new()
         .warnings(false)
         .extra_warnings(false)
@@ -318,4 +471,6 @@ fn main() {
         .file("resources/kaldi/src/online2/online-timing.cc")
         // .file("resources/kaldi/src/online2/onlinebin-util.cc")
         .compile("libkaldi");
+
+    openfst_dir
 }
@@ -0,0 +1,363 @@
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::model::VoskModel;
+use crate::recognizer::Recognizer;
+use crate::session::VoskSessionConfig;
+use crate::transcription::TranscriptionWord;
+use crate::wer::{backtrace, edit_table, normalize, EditOp};
+
+const DEFAULT_CHUNK_SIZE: usize = 8000;
+
+// Reference words searched beyond twice the length of each utterance, so
+// memory grows with the utterance rather than the whole transcript.
+const WINDOW_SLACK: usize = 100;
+
+const UNKNOWN: &str = "[unk]";
+
+/// A reference word with its position in the audio. Words that could not be
+/// aligned get times interpolated between their aligned neighbours and a
+/// confidence of zero.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AlignedWord {
+    pub word: String,
+    pub start: f32,
+    pub end: f32,
+    pub conf: f32,
+    pub aligned: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct ForcedAlignment {
+    pub words: Vec<AlignedWord>,
+}
+
+impl ForcedAlignment {
+    pub fn aligned_count(&self) -> usize {
+        self.words.iter().filter(|w| w.aligned).count()
+    }
+
+    /// Fraction of reference words that were aligned.
+    pub fn coverage(&self) -> f32 {
+        self.aligned_count() as f32 / self.words.len().max(1) as f32
+    }
+}
+
+/// Aligns a known transcript to audio by decoding with a grammar made of the
+/// transcript's words and matching the recognized words back to it.
+#[derive(Debug, Clone)]
+pub struct ForcedAligner {
+    chunk_size: usize,
+}
+
+impl Default for ForcedAligner {
+    fn default() -> Self {
+        Self {
+            chunk_size: DEFAULT_CHUNK_SIZE,
+        }
+    }
+}
+
+impl ForcedAligner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Samples fed to the recognizer at once, 8000 by default.
+    #[inline]
+    pub fn set_chunk_size(&mut self, chunk_size: usize) {
+        self.chunk_size = chunk_size.max(1);
+    }
+
+    /// The grammar restricting recognition to the words of `text`, plus
+    /// `[unk]` for anything else.
+    pub fn grammar(text: &str) -> String {
+        let mut words: Vec<String> = normalize(text)
+            .split(' ')
+            .filter(|w| !w.is_empty())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .map(str::to_string)
+            .collect();
+        words.push(UNKNOWN.to_string());
+
+        serde_json::to_string(&words).unwrap()
+    }
+
+    pub fn align(&self, model: &VoskModel, sample_rate: f32, samples: &[i16], text: &str) -> ForcedAlignment {
+        let mut builder = VoskSessionConfig::builder();
+        builder.sampling_freq(sample_rate).grammar(Self::grammar(text));
+
+        let mut session = model.create_session(builder.finish());
+
+        self.align_with(&mut session, sample_rate, samples, text)
+    }
+
+    /// Aligns with any recognizer, which should be constrained by `grammar`.
+    pub fn align_with<R: Recognizer>(&self, recognizer: &mut R, sample_rate: f32, samples: &[i16], text: &str) -> ForcedAlignment {
+        // Punctuation splits some tokens ("well-known"), so each reference
+        // token maps to a run of normalized keys.
+        let tokens: Vec<&str> = text.split_whitespace().collect();
+        let mut keys = Vec::new();
+        let mut key_token = Vec::new();
+
+        for (idx, token) in tokens.iter().enumerate() {
+            for key in normalize(token).split(' ').filter(|k| !k.is_empty()) {
+                keys.push(key.to_string());
+                key_token.push(idx);
+            }
+        }
+
+        let results = recognizer.transcribe(samples, self.chunk_size);
+        let mut key_words: Vec<Option<&TranscriptionWord>> = vec![None; keys.len()];
+        let mut cursor = 0;
+        // Words of utterances that matched nothing widen the next window, so
+        // a stretch the recognizer missed can be skipped.
+        let mut unmatched = 0;
+
+        for result in &results {
+            let hypothesis: Vec<&TranscriptionWord> = result.result.iter().collect();
+            if hypothesis.iter().all(|w| w.word == UNKNOWN) {
+                continue;
+            }
+
+            // `[unk]` never matches, but stands in for the reference word it
+            // replaced.
+            let hypothesis_keys: Vec<String> = hypothesis
+                .iter()
+                .map(|w| if w.word == UNKNOWN { w.word.clone() } else { normalize(&w.word) })
+                .collect();
+            let window_end = keys
+                .len()
+                .min(cursor + 2 * (hypothesis.len() + unmatched) + WINDOW_SLACK);
+
+            let matches = match_window(&keys[cursor..window_end], &hypothesis_keys);
+            match matches.last() {
+                Some(&(last, _)) => {
+                    for &(r, h) in &matches {
+                        key_words[cursor + r] = Some(hypothesis[h]);
+                    }
+                    cursor += last + 1;
+                    unmatched = 0;
+                }
+                None => unmatched += hypothesis.len(),
+            }
+        }
+
+        let mut words: Vec<AlignedWord> = tokens
+            .iter()
+            .map(|token| AlignedWord {
+                word: token.to_string(),
+                start: 0.0,
+                end: 0.0,
+                conf: 0.0,
+                aligned: false,
+            })
+            .collect();
+
+        // A token is aligned when all of its keys are.
+        let mut complete = vec![true; tokens.len()];
+        let mut seen = vec![false; tokens.len()];
+        let mut confs = vec![Vec::new(); tokens.len()];
+
+        for (key, word) in key_words.iter().enumerate() {
+            let idx = key_token[key];
+            let aligned = &mut words[idx];
+
+            match word {
+                Some(word) => {
+                    if !seen[idx] {
+                        aligned.start = word.start;
+                    }
+                    aligned.end = word.end;
+                    confs[idx].push(word.conf);
+                }
+                None => complete[idx] = false,
+            }
+            seen[idx] = true;
+        }
+
+        for (idx, word) in words.iter_mut().enumerate() {
+            word.aligned = seen[idx] && complete[idx];
+            if word.aligned {
+                word.conf = confs[idx].iter().sum::<f32>() / confs[idx].len() as f32;
+            }
+        }
+
+        interpolate(&mut words, samples.len() as f32 / sample_rate);
+
+        ForcedAlignment { words }
+    }
+}
+
+/// Matches an utterance to a reference window and returns the matched
+/// (reference, hypothesis) index pairs in order. Reference words before and
+/// after the utterance are free, as the recognizer may have missed them or
+/// they belong to later utterances.
+fn match_window(reference: &[String], hypothesis: &[String]) -> Vec<(usize, usize)> {
+    let (ops, costs) = edit_table(reference, hypothesis, true);
+
+    // Of equally cheap ends, the first is the closest to the previous match.
+    let end = (0..costs.len()).min_by_key(|&i| costs[i]).unwrap_or(0);
+
+    backtrace(&ops, hypothesis.len() + 1, end, hypothesis.len())
+        .into_iter()
+        .filter_map(|(op, r, h)| match op {
+            EditOp::Match => Some((r?, h?)),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Spreads each run of unaligned words evenly over the gap between the
+/// aligned words around it.
+fn interpolate(words: &mut [AlignedWord], duration: f32) {
+    let mut idx = 0;
+
+    while idx < words.len() {
+        if words[idx].aligned {
+            idx += 1;
+            continue;
+        }
+
+        let run_start = idx;
+        while idx < words.len() && !words[idx].aligned {
+            idx += 1;
+        }
+
+        let gap_start = if run_start > 0 { words[run_start - 1].end } else { 0.0 };
+        let gap_end = words.get(idx).map_or(duration, |w| w.start).max(gap_start);
+        let step = (gap_end - gap_start) / (idx - run_start) as f32;
+
+        for (n, word) in words[run_start..idx].iter_mut().enumerate() {
+            word.start = gap_start + step * n as f32;
+            word.end = gap_start + step * (n + 1) as f32;
+            word.conf = 0.0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::recognizer::FakeRecognizer;
+    use crate::transcription::TranscriptionResult;
+
+    /// An utterance of one-second words starting at `start`.
+    fn utterance(words: &[&str], start: f32) -> TranscriptionResult {
+        TranscriptionResult {
+            text: words.join(" "),
+            result: words
+                .iter()
+                .enumerate()
+                .map(|(idx, word)| TranscriptionWord {
+                    word: word.to_string(),
+                    start: start + idx as f32,
+                    end: start + idx as f32 + 1.0,
+                    conf: 0.5,
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    /// Aligns `text` to `duration` seconds of audio at one sample per second,
+    /// with each utterance completing at its own sample.
+    fn align(text: &str, duration: usize, utterances: &[TranscriptionResult]) -> ForcedAlignment {
+        let mut builder = FakeRecognizer::builder();
+        for (idx, utterance) in utterances.iter().enumerate() {
+            builder.result(idx + 1, utterance.clone());
+        }
+
+        let mut aligner = ForcedAligner::new();
+        aligner.set_chunk_size(1);
+
+        aligner.align_with(&mut builder.finish(), 1.0, &vec![0; duration], text)
+    }
+
+    fn times(alignment: &ForcedAlignment) -> Vec<(f32, f32, bool)> {
+        alignment.words.iter().map(|w| (w.start, w.end, w.aligned)).collect()
+    }
+
+    #[test]
+    fn interpolates_skipped_words_at_both_ends() {
+        let alignment = align("a b c d e f g", 18, &[utterance(&["c", "d"], 4.0)]);
+
+        assert_eq!(
+            times(&alignment),
+            [
+                (0.0, 2.0, false),
+                (2.0, 4.0, false),
+                (4.0, 5.0, true),
+                (5.0, 6.0, true),
+                (6.0, 10.0, false),
+                (10.0, 14.0, false),
+                (14.0, 18.0, false),
+            ]
+        );
+        assert_eq!(alignment.words[0].conf, 0.0);
+        assert_eq!(alignment.words[2].conf, 0.5);
+        assert_eq!(alignment.aligned_count(), 2);
+    }
+
+    #[test]
+    fn interpolates_a_gap_between_utterances() {
+        let alignment = align(
+            "one two three four five",
+            20,
+            &[utterance(&["one", "two"], 0.0), utterance(&["five"], 8.0)],
+        );
+
+        assert_eq!(times(&alignment)[2..], [(2.0, 5.0, false), (5.0, 8.0, false), (8.0, 9.0, true)]);
+    }
+
+    #[test]
+    fn unknown_words_take_the_place_of_a_reference_word() {
+        let alignment = align("sat on the mat", 10, &[utterance(&["sat", "on", "[unk]", "mat"], 0.0)]);
+
+        assert_eq!(
+            alignment.words.iter().map(|w| w.aligned).collect::<Vec<_>>(),
+            [true, true, false, true]
+        );
+        assert_eq!((alignment.words[3].start, alignment.words[3].end), (3.0, 4.0));
+    }
+
+    #[test]
+    fn split_tokens_span_their_parts() {
+        let alignment = align("A well-known fact.", 10, &[utterance(&["a", "well", "known", "fact"], 0.0)]);
+
+        let word = &alignment.words[1];
+        assert_eq!(word.word, "well-known");
+        assert_eq!((word.start, word.end, word.aligned), (1.0, 3.0, true));
+        assert_eq!(alignment.words[2].word, "fact.");
+        assert_eq!(alignment.coverage(), 1.0);
+    }
+
+    #[test]
+    fn transcripts_longer_than_a_window() {
+        let reference: Vec<String> = (0..1000).map(|idx| format!("w{}", idx)).collect();
+
+        // Ten-word utterances, with words 400 to 449 never recognized.
+        let utterances: Vec<TranscriptionResult> = (0..100)
+            .filter(|chunk| !(40..45).contains(chunk))
+            .map(|chunk| {
+                let words: Vec<&str> = reference[chunk * 10..chunk * 10 + 10].iter().map(String::as_str).collect();
+                utterance(&words, chunk as f32 * 10.0)
+            })
+            .collect();
+
+        let alignment = align(&reference.join(" "), 1000, &utterances);
+
+        assert_eq!(alignment.aligned_count(), 950);
+        for (idx, word) in alignment.words.iter().enumerate() {
+            assert_eq!(word.aligned, !(400..450).contains(&idx), "{}", word.word);
+            assert_eq!(word.start, idx as f32, "{}", word.word);
+        }
+    }
+
+    #[test]
+    fn grammar_lists_each_word_once() {
+        assert_eq!(ForcedAligner::grammar("The cat, the hat."), r#"["cat","hat","the","[unk]"]"#);
+    }
+}
@@ -0,0 +1,355 @@
+use std::fs;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Component, Path, PathBuf};
+
+use flate2::read::GzDecoder;
+use sha2::{Digest, Sha256};
+use tar::{Archive, EntryType};
+
+use crate::error::VoskError;
+use crate::model::VoskModel;
+use crate::model_info::ModelInfo;
+
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+const ZIP_MAGIC: &[u8] = b"PK\x03\x04";
+const TAR_MAGIC_OFFSET: usize = 257;
+const TAR_MAGIC: &[u8] = b"ustar";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ArchiveKind {
+    Zip,
+    TarGz,
+    Tar,
+}
+
+/// A model packed as `.zip`, `.tar.gz` or `.tar`, as published on the Vosk
+/// model page. Archives are extracted once into a cache directory keyed by
+/// the SHA-256 of their contents and reused afterwards.
+pub struct ModelArchive {
+    path: PathBuf,
+    cache_dir: PathBuf,
+}
+
+impl ModelArchive {
+    /// Uses `$VOSK_MODEL_CACHE` as the cache directory, falling back to
+    /// `vosk-models` in the system temp directory.
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        let cache_dir = std::env::var_os("VOSK_MODEL_CACHE")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| std::env::temp_dir().join("vosk-models"));
+
+        Self {
+            path: path.into(),
+            cache_dir,
+        }
+    }
+
+    #[inline]
+    pub fn set_cache_dir<P: Into<PathBuf>>(&mut self, dir: P) {
+        self.cache_dir = dir.into();
+    }
+
+    /// Extracts the archive unless a previous run already did, and returns
+    /// the model root inside the cache.
+    pub fn extract(&self) -> Result<PathBuf, VoskError> {
+        let mut file = fs::File::open(&self.path)?;
+        let kind = detect_kind(&mut file)?;
+        let hash = sha256(&mut file)?;
+
+        let target = self.cache_dir.join(&hash);
+        if target.is_dir() {
+            return Ok(model_root(&target));
+        }
+
+        fs::create_dir_all(&self.cache_dir)?;
+
+        let staging = self.cache_dir.join(format!(".{}.{}.tmp", hash, std::process::id()));
+        if staging.exists() {
+            fs::remove_dir_all(&staging)?;
+        }
+        fs::create_dir_all(&staging)?;
+
+        file.seek(SeekFrom::Start(0))?;
+        let unpacked = match kind {
+            ArchiveKind::Zip => unpack_zip(file, &staging),
+            ArchiveKind::TarGz => unpack_tar(GzDecoder::new(file), &staging),
+            ArchiveKind::Tar => unpack_tar(file, &staging),
+        };
+
+        if let Err(err) = unpacked {
+            let _ = fs::remove_dir_all(&staging);
+            return Err(err);
+        }
+
+        // Another process may have finished extracting the same archive in the
+        // meantime; its copy is as good as ours.
+        if let Err(err) = fs::rename(&staging, &target) {
+            let _ = fs::remove_dir_all(&staging);
+            if !target.is_dir() {
+                return Err(err.into());
+            }
+        }
+
+        Ok(model_root(&target))
+    }
+
+    pub fn load(&self) -> Result<VoskModel, VoskError> {
+        VoskModel::try_new(self.extract()?)
+    }
+}
+
+impl VoskModel {
+    /// Loads a model from an archive, see `ModelArchive`.
+    pub fn from_archive<P: Into<PathBuf>>(path: P) -> Result<Self, VoskError> {
+        ModelArchive::new(path).load()
+    }
+}
+
+fn detect_kind(file: &mut fs::File) -> Result<ArchiveKind, VoskError> {
+    let mut header = [0u8; TAR_MAGIC_OFFSET + 8];
+    let mut len = 0;
+    while len < header.len() {
+        match file.read(&mut header[len..])? {
+            0 => break,
+            n => len += n,
+        }
+    }
+    let header = &header[..len];
+
+    if header.starts_with(ZIP_MAGIC) {
+        Ok(ArchiveKind::Zip)
+    } else if header.starts_with(GZIP_MAGIC) {
+        Ok(ArchiveKind::TarGz)
+    } else if header.len() > TAR_MAGIC_OFFSET && header[TAR_MAGIC_OFFSET..].starts_with(TAR_MAGIC) {
+        Ok(ArchiveKind::Tar)
+    } else {
+        Err(VoskError::Archive("unsupported archive format, expected .zip, .tar.gz or .tar".to_string()))
+    }
+}
+
+fn sha256(file: &mut fs::File) -> Result<String, VoskError> {
+    file.seek(SeekFrom::Start(0))?;
+
+    let mut hasher = Sha256::new();
+    io::copy(file, &mut hasher)?;
+
+    Ok(hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// Rejects absolute paths and `..` so entries can't escape the target directory.
+fn sanitize(name: &Path) -> Result<PathBuf, VoskError> {
+    let mut path = PathBuf::new();
+
+    for component in name.components() {
+        match component {
+            Component::Normal(part) => path.push(part),
+            Component::CurDir => {}
+            _ => return Err(VoskError::Archive(format!("unsafe path in archive: {}", name.display()))),
+        }
+    }
+
+    Ok(path)
+}
+
+fn unpack_tar<R: Read>(reader: R, dest: &Path) -> Result<(), VoskError> {
+    let mut archive = Archive::new(reader);
+
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let path = sanitize(&entry.path()?)?;
+
+        match entry.header().entry_type() {
+            EntryType::Directory => fs::create_dir_all(dest.join(&path))?,
+            EntryType::Regular | EntryType::Continuous => {
+                let target = dest.join(&path);
+                if let Some(parent) = target.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                entry.unpack(&target)?;
+            }
+            // Links could point outside of the cache; models don't need them.
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+fn unpack_zip<R: Read + Seek>(reader: R, dest: &Path) -> Result<(), VoskError> {
+    let mut archive = zip::ZipArchive::new(reader).map_err(|e| VoskError::Archive(e.to_string()))?;
+
+    for idx in 0..archive.len() {
+        let mut entry = archive.by_index(idx).map_err(|e| VoskError::Archive(e.to_string()))?;
+        let path = sanitize(Path::new(entry.name()))?;
+        let target = dest.join(&path);
+
+        if entry.is_dir() {
+            fs::create_dir_all(&target)?;
+            continue;
+        }
+
+        if entry.unix_mode().is_some_and(|mode| mode & 0o170000 == 0o120000) {
+            continue;
+        }
+
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut out = fs::File::create(&target)?;
+        io::copy(&mut entry, &mut out)?;
+    }
+
+    Ok(())
+}
+
+/// Model archives usually wrap everything in a single top-level directory.
+fn model_root(dir: &Path) -> PathBuf {
+    if ModelInfo::inspect(dir).is_valid() {
+        return dir.to_path_buf();
+    }
+
+    let entries: Vec<PathBuf> = match fs::read_dir(dir) {
+        Ok(entries) => entries.filter_map(Result::ok).map(|e| e.path()).collect(),
+        Err(_) => return dir.to_path_buf(),
+    };
+
+    match entries.as_slice() {
+        [single] if single.is_dir() => single.clone(),
+        _ => dir.to_path_buf(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Cursor, Write};
+
+    fn tar(entries: &[(&str, EntryType)]) -> Vec<u8> {
+        let mut builder = tar::Builder::new(Vec::new());
+
+        for (name, kind) in entries {
+            let mut header = tar::Header::new_gnu();
+            // `set_path` refuses the unsafe names these tests need.
+            header.as_old_mut().name[..name.len()].copy_from_slice(name.as_bytes());
+            header.set_entry_type(*kind);
+            header.set_mode(0o644);
+            if *kind == EntryType::Symlink {
+                header.set_link_name("/etc/passwd").unwrap();
+            }
+            header.set_size(if *kind == EntryType::Regular { 4 } else { 0 });
+            header.set_cksum();
+
+            let data: &[u8] = if *kind == EntryType::Regular { b"data" } else { b"" };
+            builder.append(&header, data).unwrap();
+        }
+
+        builder.into_inner().unwrap()
+    }
+
+    fn zip(names: &[&str]) -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+
+        for name in names {
+            writer.start_file(*name, zip::write::FileOptions::default()).unwrap();
+            writer.write_all(b"data").unwrap();
+        }
+
+        writer.finish().unwrap().into_inner()
+    }
+
+    fn unsafe_path(result: Result<(), VoskError>, name: &str) {
+        match result {
+            Err(VoskError::Archive(msg)) => assert_eq!(msg, format!("unsafe path in archive: {}", name)),
+            other => panic!("{} was accepted: {:?}", name, other.err()),
+        }
+    }
+
+    #[test]
+    fn sanitize_paths() {
+        assert_eq!(sanitize(Path::new("model/am/final.mdl")).unwrap(), Path::new("model/am/final.mdl"));
+        assert_eq!(sanitize(Path::new("./model/./conf")).unwrap(), Path::new("model/conf"));
+        assert!(sanitize(Path::new("../evil")).is_err());
+        assert!(sanitize(Path::new("model/../../evil")).is_err());
+        assert!(sanitize(Path::new("/abs")).is_err());
+    }
+
+    #[test]
+    fn tar_rejects_escaping_entries() {
+        for name in ["../evil", "model/../../evil", "/abs"] {
+            let dir = tempfile::tempdir().unwrap();
+            let dest = dir.path().join("dest");
+            fs::create_dir(&dest).unwrap();
+
+            let archive = tar(&[("model/conf", EntryType::Directory), (name, EntryType::Regular)]);
+            unsafe_path(unpack_tar(archive.as_slice(), &dest), name);
+
+            assert!(!dir.path().join("evil").exists());
+        }
+    }
+
+    #[test]
+    fn tar_skips_symlinks() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = tar(&[
+            ("model/am/final.mdl", EntryType::Regular),
+            ("model/link", EntryType::Symlink),
+        ]);
+
+        unpack_tar(archive.as_slice(), dir.path()).unwrap();
+
+        assert_eq!(fs::read(dir.path().join("model/am/final.mdl")).unwrap(), b"data");
+        assert!(fs::symlink_metadata(dir.path().join("model/link")).is_err());
+    }
+
+    #[test]
+    fn zip_rejects_escaping_entries() {
+        for name in ["../evil", "model/../../evil", "/abs"] {
+            let dir = tempfile::tempdir().unwrap();
+            let dest = dir.path().join("dest");
+            fs::create_dir(&dest).unwrap();
+
+            let archive = zip(&["model/am/final.mdl", name]);
+            unsafe_path(unpack_zip(Cursor::new(archive), &dest), name);
+
+            assert!(!dir.path().join("evil").exists());
+        }
+    }
+
+    #[test]
+    fn zip_skips_symlinks() {
+        let mut archive = zip(&["model/am/final.mdl", "model/link"]);
+
+        // The writer has no symlinks, so mark the second central directory
+        // entry as one through its external attributes.
+        let central = archive
+            .windows(4)
+            .enumerate()
+            .filter(|(_, sig)| *sig == b"PK\x01\x02")
+            .map(|(idx, _)| idx)
+            .nth(1)
+            .unwrap();
+        archive[central + 38..central + 42].copy_from_slice(&(0o120777u32 << 16).to_le_bytes());
+
+        let dir = tempfile::tempdir().unwrap();
+        unpack_zip(Cursor::new(archive), dir.path()).unwrap();
+
+        assert_eq!(fs::read(dir.path().join("model/am/final.mdl")).unwrap(), b"data");
+        assert!(fs::symlink_metadata(dir.path().join("model/link")).is_err());
+    }
+
+    #[test]
+    fn detect_archive_kinds() {
+        let dir = tempfile::tempdir().unwrap();
+        let kind = |name: &str, data: &[u8]| {
+            let path = dir.path().join(name);
+            fs::write(&path, data).unwrap();
+            detect_kind(&mut fs::File::open(&path).unwrap()).ok()
+        };
+
+        assert_eq!(kind("a.zip", &zip(&["a"])), Some(ArchiveKind::Zip));
+        assert_eq!(kind("a.tar", &tar(&[("a", EntryType::Regular)])), Some(ArchiveKind::Tar));
+        assert_eq!(kind("a.tar.gz", &[0x1f, 0x8b, 8, 0]), Some(ArchiveKind::TarGz));
+        assert_eq!(kind("a.txt", b"not an archive"), None);
+    }
+}
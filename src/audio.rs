@@ -0,0 +1,63 @@
+use std::fs;
+use std::io::{BufReader, Read};
+use std::path::Path;
+
+use hound::{SampleFormat, WavReader};
+
+use crate::error::VoskError;
+
+/// 16-bit mono PCM ready to be fed to a session.
+#[derive(Debug, Clone)]
+pub struct Audio {
+    pub samples: Vec<i16>,
+    pub sample_rate: u32,
+}
+
+impl Audio {
+    /// Duration in seconds.
+    #[inline]
+    pub fn duration(&self) -> f32 {
+        self.samples.len() as f32 / self.sample_rate as f32
+    }
+}
+
+pub fn read_wav<P: AsRef<Path>>(path: P) -> Result<Audio, VoskError> {
+    let file = fs::File::open(path)?;
+
+    read_wav_from(BufReader::new(file))
+}
+
+/// Reads a WAV stream of any integer or float sample format, mixing
+/// multi-channel audio down to mono.
+pub fn read_wav_from<R: Read>(reader: R) -> Result<Audio, VoskError> {
+    let reader = WavReader::new(reader).map_err(|e| VoskError::Audio(e.to_string()))?;
+    let spec = reader.spec();
+
+    let samples: Vec<i32> = match (spec.sample_format, spec.bits_per_sample) {
+        (SampleFormat::Float, _) => reader
+            .into_samples::<f32>()
+            .map(|s| s.map(|s| (s.clamp(-1.0, 1.0) * i16::MAX as f32) as i32))
+            .collect::<Result<_, _>>(),
+        (SampleFormat::Int, bits) => {
+            let shift = bits.saturating_sub(16);
+            let scale = 16u16.saturating_sub(bits);
+
+            reader
+                .into_samples::<i32>()
+                .map(|s| s.map(|s| (s >> shift) << scale))
+                .collect::<Result<_, _>>()
+        }
+    }
+    .map_err(|e| VoskError::Audio(e.to_string()))?;
+
+    let channels = spec.channels.max(1) as usize;
+    let samples = samples
+        .chunks(channels)
+        .map(|frame| (frame.iter().sum::<i32>() / frame.len() as i32) as i16)
+        .collect();
+
+    Ok(Audio {
+        samples,
+        sample_rate: spec.sample_rate,
+    })
+}
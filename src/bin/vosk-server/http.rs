@@ -0,0 +1,239 @@
+use std::io::Read;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Arc;
+use std::thread;
+
+use serde_json::json;
+use tiny_http::{Header, Method, Request, Response, Server};
+use vosk::{OutputFormat, Transcript, VoskSessionConfig};
+
+use crate::Models;
+
+struct Options {
+    model: Option<String>,
+    grammar: Option<String>,
+    format: OutputFormat,
+    sample_rate: Option<f32>,
+}
+
+struct HttpError {
+    status: u16,
+    message: String,
+}
+
+impl HttpError {
+    fn new<M: Into<String>>(status: u16, message: M) -> Self {
+        Self {
+            status,
+            message: message.into(),
+        }
+    }
+}
+
+/// Serves `POST /transcribe` with `workers` threads, each decoding one
+/// request at a time; further requests wait in the accept queue. Bodies
+/// larger than `max_body_size` bytes are rejected.
+pub fn serve(server: Server, models: Arc<Models>, sample_rate: f32, workers: usize, max_body_size: u64) {
+    let server = Arc::new(server);
+
+    let handles: Vec<_> = (0..workers.max(1))
+        .map(|_| {
+            let server = server.clone();
+            let models = models.clone();
+
+            thread::spawn(move || loop {
+                let request = match server.recv() {
+                    Ok(request) => request,
+                    // The listener is gone, retrying would spin.
+                    Err(err) => {
+                        eprintln!("vosk-server: {}", err);
+                        break;
+                    }
+                };
+
+                // A dropped request is answered with a 500, so a panic only
+                // costs the request that caused it, not the worker.
+                let handled = panic::catch_unwind(AssertUnwindSafe(|| {
+                    handle(request, &models, sample_rate, max_body_size)
+                }));
+                if handled.is_err() {
+                    eprintln!("vosk-server: worker recovered from a panic");
+                }
+            })
+        })
+        .collect();
+
+    for handle in handles {
+        let _ = handle.join();
+    }
+}
+
+fn handle(mut request: Request, models: &Models, sample_rate: f32, max_body_size: u64) {
+    let response = match transcribe(&mut request, models, sample_rate, max_body_size) {
+        Ok((format, body)) => Response::from_data(body).with_header(content_type(format)),
+        Err(err) => Response::from_data(json!({ "error": err.message }).to_string())
+            .with_status_code(err.status)
+            .with_header(content_type(OutputFormat::Json)),
+    };
+
+    if let Err(err) = request.respond(response) {
+        eprintln!("vosk-server: {}", err);
+    }
+}
+
+fn transcribe(
+    request: &mut Request,
+    models: &Models,
+    sample_rate: f32,
+    max_body_size: u64,
+) -> Result<(OutputFormat, Vec<u8>), HttpError> {
+    let url = request.url().to_string();
+    let (path, query) = match url.find('?') {
+        Some(idx) => (&url[..idx], &url[idx + 1..]),
+        None => (url.as_str(), ""),
+    };
+
+    if path != "/transcribe" {
+        return Err(HttpError::new(404, format!("no such endpoint {}", path)));
+    }
+
+    if *request.method() != Method::Post {
+        return Err(HttpError::new(405, "use POST"));
+    }
+
+    let options = parse_options(query)?;
+
+    let mut body = Vec::new();
+    request
+        .as_reader()
+        .take(max_body_size + 1)
+        .read_to_end(&mut body)
+        .map_err(|err| HttpError::new(400, err.to_string()))?;
+
+    if body.len() as u64 > max_body_size {
+        return Err(HttpError::new(413, "request body is too large"));
+    }
+
+    let (samples, rate) = if body.starts_with(b"RIFF") {
+        let audio = vosk::read_wav_from(body.as_slice()).map_err(|err| HttpError::new(400, err.to_string()))?;
+        (audio.samples, options.sample_rate.unwrap_or(audio.sample_rate as f32))
+    } else {
+        let samples = body
+            .chunks_exact(2)
+            .map(|b| i16::from_le_bytes([b[0], b[1]]))
+            .collect();
+        (samples, options.sample_rate.unwrap_or(sample_rate))
+    };
+
+    let model = models
+        .get(options.model.as_deref())
+        .map_err(|err| HttpError::new(404, err))?;
+
+    let mut builder = VoskSessionConfig::builder();
+    builder.sampling_freq(rate);
+    if let Some(grammar) = &options.grammar {
+        builder.grammar(grammar);
+    }
+
+    let transcript = Transcript::new("request", model.transcribe(builder.finish(), &samples));
+
+    let mut out = Vec::new();
+    transcript
+        .write(options.format, &mut out)
+        .map_err(|err| HttpError::new(500, err.to_string()))?;
+
+    Ok((options.format, out))
+}
+
+fn parse_options(query: &str) -> Result<Options, HttpError> {
+    let mut options = Options {
+        model: None,
+        grammar: None,
+        format: OutputFormat::Json,
+        sample_rate: None,
+    };
+
+    for pair in query.split('&').filter(|p| !p.is_empty()) {
+        let (key, value) = match pair.find('=') {
+            Some(idx) => (&pair[..idx], percent_decode(&pair[idx + 1..])),
+            None => (pair, String::new()),
+        };
+
+        match key {
+            "model" => options.model = Some(value),
+            "grammar" => options.grammar = Some(grammar(&value)?),
+            "format" => options.format = value.parse().map_err(|err: String| HttpError::new(400, err))?,
+            "sample_rate" => {
+                let rate = value
+                    .parse::<f32>()
+                    .ok()
+                    .filter(|r| *r > 0.0)
+                    .ok_or_else(|| HttpError::new(400, format!("invalid sample_rate {:?}", value)))?;
+                options.sample_rate = Some(rate);
+            }
+            _ => return Err(HttpError::new(400, format!("unknown option {:?}", key))),
+        }
+    }
+
+    Ok(options)
+}
+
+/// Accepts a JSON array of phrases, or comma separated phrases.
+fn grammar(value: &str) -> Result<String, HttpError> {
+    let value = value.trim();
+
+    let phrases: Vec<String> = if value.starts_with('[') {
+        serde_json::from_str(value).map_err(|err| HttpError::new(400, format!("invalid grammar: {}", err)))?
+    } else {
+        value
+            .split(',')
+            .map(str::trim)
+            .filter(|p| !p.is_empty())
+            .map(str::to_string)
+            .collect()
+    };
+
+    if phrases.iter().any(|p| p.contains('\0')) {
+        return Err(HttpError::new(400, "invalid grammar: phrases must not contain NUL"));
+    }
+
+    Ok(serde_json::to_string(&phrases).unwrap())
+}
+
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut idx = 0;
+
+    while idx < bytes.len() {
+        match bytes[idx] {
+            b'+' => out.push(b' '),
+            b'%' if idx + 2 < bytes.len() => match hex_byte(&bytes[idx + 1..idx + 3]) {
+                Some(byte) => {
+                    out.push(byte);
+                    idx += 2;
+                }
+                None => out.push(b'%'),
+            },
+            byte => out.push(byte),
+        }
+        idx += 1;
+    }
+
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+fn hex_byte(digits: &[u8]) -> Option<u8> {
+    let hex = std::str::from_utf8(digits).ok()?;
+    u8::from_str_radix(hex, 16).ok()
+}
+
+fn content_type(format: OutputFormat) -> Header {
+    let value: &[u8] = match format {
+        OutputFormat::Json => b"application/json",
+        OutputFormat::Vtt => b"text/vtt; charset=utf-8",
+        _ => b"text/plain; charset=utf-8",
+    };
+
+    Header::from_bytes(&b"Content-Type"[..], value).unwrap()
+}
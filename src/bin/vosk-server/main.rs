@@ -0,0 +1,154 @@
+use std::net::TcpListener;
+use std::process;
+use std::sync::Arc;
+use std::thread;
+
+use clap::{App, Arg};
+use vosk::{ModelRegistry, VoskModel};
+
+mod http;
+mod websocket;
+
+/// The default model plus any models from `--models-dir`, selectable by name.
+pub struct Models {
+    default: Option<Arc<VoskModel>>,
+    registry: Option<ModelRegistry>,
+}
+
+impl Models {
+    pub fn get(&self, name: Option<&str>) -> Result<Arc<VoskModel>, String> {
+        match (name, &self.registry, &self.default) {
+            (Some(name), Some(registry), _) => registry.get(name).map_err(|err| err.to_string()),
+            (Some(name), None, _) => Err(format!("unknown model {:?}, the server has no --models-dir", name)),
+            (None, _, Some(model)) => Ok(model.clone()),
+            (None, _, None) => Err("no model selected and the server has no default model".to_string()),
+        }
+    }
+}
+
+fn main() {
+    let matches = App::new("vosk-server")
+        .version(env!("CARGO_PKG_VERSION"))
+        .about("Speech recognition server speaking the vosk-server WebSocket protocol or plain HTTP")
+        .arg(Arg::with_name("model")
+            .short("m")
+            .long("model")
+            .takes_value(true)
+            .env("VOSK_MODEL_PATH")
+            .required_unless("models-dir")
+            .help("Model directory"))
+        .arg(Arg::with_name("models-dir")
+            .long("models-dir")
+            .takes_value(true)
+            .env("VOSK_MODELS_DIR")
+            .help("Directory of models clients may select by name (HTTP only)"))
+        .arg(Arg::with_name("protocol")
+            .short("p")
+            .long("protocol")
+            .takes_value(true)
+            .possible_values(&["websocket", "http"])
+            .default_value("websocket")
+            .help("Protocol to serve"))
+        .arg(Arg::with_name("listen")
+            .short("l")
+            .long("listen")
+            .takes_value(true)
+            .env("VOSK_SERVER_LISTEN")
+            .default_value("0.0.0.0:2700")
+            .help("Address to accept connections on"))
+        .arg(Arg::with_name("sample-rate")
+            .short("r")
+            .long("sample-rate")
+            .takes_value(true)
+            .env("VOSK_SAMPLE_RATE")
+            .default_value("16000")
+            .help("Sample rate used unless the client sends one"))
+        .arg(Arg::with_name("workers")
+            .short("w")
+            .long("workers")
+            .takes_value(true)
+            .env("VOSK_SERVER_WORKERS")
+            .default_value("4")
+            .help("Maximum number of HTTP requests decoded at once"))
+        .arg(Arg::with_name("max-body-size")
+            .long("max-body-size")
+            .takes_value(true)
+            .env("VOSK_SERVER_MAX_BODY_SIZE")
+            .default_value("32")
+            .help("Largest HTTP request body accepted, in megabytes"))
+        .get_matches();
+
+    let sample_rate: f32 = match matches.value_of("sample-rate").unwrap().parse() {
+        Ok(rate) => rate,
+        Err(_) => fail("invalid sample rate"),
+    };
+
+    let workers: usize = match matches.value_of("workers").unwrap().parse() {
+        Ok(workers) if workers > 0 => workers,
+        _ => fail("invalid worker count"),
+    };
+
+    let max_body_size = match matches.value_of("max-body-size").unwrap().parse::<u64>() {
+        Ok(size) if size > 0 => size.saturating_mul(1024 * 1024),
+        _ => fail("invalid maximum body size"),
+    };
+
+    let default = matches.value_of("model").map(|path| match VoskModel::try_new(path) {
+        Ok(model) => Arc::new(model),
+        Err(err) => fail(err),
+    });
+
+    let registry = matches.value_of("models-dir").map(|dir| match ModelRegistry::scan(dir) {
+        Ok(registry) => registry,
+        Err(err) => fail(format!("{}: {}", dir, err)),
+    });
+
+    let models = Models { default, registry };
+    let listen = matches.value_of("listen").unwrap();
+
+    match matches.value_of("protocol").unwrap() {
+        "http" => match tiny_http::Server::http(listen) {
+            Ok(server) => http::serve(server, Arc::new(models), sample_rate, workers, max_body_size),
+            Err(err) => fail(format!("{}: {}", listen, err)),
+        },
+        _ => match models.default {
+            Some(model) => serve_websocket(listen, model, sample_rate),
+            None => fail("the websocket protocol needs --model"),
+        },
+    }
+}
+
+fn serve_websocket(listen: &str, model: Arc<VoskModel>, sample_rate: f32) {
+    let listener = match TcpListener::bind(listen) {
+        Ok(listener) => listener,
+        Err(err) => fail(format!("{}: {}", listen, err)),
+    };
+
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(err) => {
+                eprintln!("vosk-server: accept failed: {}", err);
+                continue;
+            }
+        };
+
+        let model = model.clone();
+        thread::spawn(move || {
+            let peer = stream.peer_addr().map(|a| a.to_string()).unwrap_or_default();
+
+            let result = tungstenite::accept(stream)
+                .map_err(|err| err.to_string())
+                .and_then(|ws| websocket::handle(ws, model, sample_rate).map_err(|err| err.to_string()));
+
+            if let Err(err) = result {
+                eprintln!("vosk-server: {}: {}", peer, err);
+            }
+        });
+    }
+}
+
+fn fail<E: std::fmt::Display>(err: E) -> ! {
+    eprintln!("vosk-server: error: {}", err);
+    process::exit(1);
+}
@@ -0,0 +1,186 @@
+use std::net::TcpStream;
+use std::sync::Arc;
+
+use serde::{Deserialize, Deserializer};
+use serde_json::{json, Value};
+use tungstenite::{Message, WebSocket};
+use vosk::{VoskModel, VoskSession, VoskSessionConfig};
+
+/// Per-connection settings sent as `{"config": {...}}` before the audio.
+#[derive(Deserialize, Debug, Default)]
+struct Config {
+    sample_rate: Option<f32>,
+    #[serde(default, deserialize_with = "flag")]
+    words: Option<bool>,
+    phrase_list: Option<Vec<String>>,
+}
+
+/// Like the reference server, which calls `bool()` on it, takes `1` and `0`
+/// as well as `true` and `false`.
+fn flag<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<bool>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Flag {
+        Bool(bool),
+        Number(f64),
+    }
+
+    Ok(Option::<Flag>::deserialize(deserializer)?.map(|flag| match flag {
+        Flag::Bool(value) => value,
+        Flag::Number(value) => value != 0.0,
+    }))
+}
+
+#[derive(Deserialize, Debug)]
+struct ControlMessage {
+    config: Option<Config>,
+    eof: Option<Value>,
+}
+
+struct Connection {
+    model: Arc<VoskModel>,
+    sample_rate: f32,
+    words: bool,
+    grammar: Option<String>,
+    session: Option<VoskSession>,
+}
+
+impl Connection {
+    fn new(model: Arc<VoskModel>, sample_rate: f32) -> Self {
+        Self {
+            model,
+            sample_rate,
+            words: true,
+            grammar: None,
+            session: None,
+        }
+    }
+
+    /// Applies `config` to the next utterance. An utterance in progress is
+    /// finished first and its result returned.
+    fn configure(&mut self, config: Config) -> Result<Option<String>, String> {
+        if let Some(phrase_list) = &config.phrase_list {
+            if phrase_list.iter().any(|p| p.contains('\0')) {
+                return Err("phrase_list entries must not contain NUL".to_string());
+            }
+        }
+
+        // Buffered audio belongs to the old settings; the next audio frame
+        // starts a session with the new ones.
+        let flushed = self.session.is_some().then(|| self.finish());
+
+        if let Some(sample_rate) = config.sample_rate {
+            self.sample_rate = sample_rate;
+        }
+
+        if let Some(words) = config.words {
+            self.words = words;
+        }
+
+        if let Some(phrase_list) = config.phrase_list {
+            self.grammar = Some(serde_json::to_string(&phrase_list).unwrap());
+        }
+
+        Ok(flushed)
+    }
+
+    fn session(&mut self) -> &mut VoskSession {
+        if self.session.is_none() {
+            let mut builder = VoskSessionConfig::builder();
+            builder.sampling_freq(self.sample_rate);
+            if let Some(grammar) = &self.grammar {
+                builder.grammar(grammar);
+            }
+
+            self.session = Some(self.model.create_session(builder.finish()));
+        }
+
+        self.session.as_mut().unwrap()
+    }
+
+    fn accept(&mut self, data: &[u8]) -> String {
+        let samples: Vec<i16> = data
+            .chunks_exact(2)
+            .map(|b| i16::from_le_bytes([b[0], b[1]]))
+            .collect();
+
+        let model = self.model.clone();
+        let session = self.session();
+
+        if model.feed(session, &samples) {
+            let result = model.get_result(session);
+            self.result_json(serde_json::to_value(result).unwrap())
+        } else {
+            serde_json::to_string(&model.get_partial_result(session)).unwrap()
+        }
+    }
+
+    fn finish(&mut self) -> String {
+        self.session();
+        let session = self.session.take().unwrap();
+        let result = self.model.get_final_result(session);
+
+        self.result_json(serde_json::to_value(result).unwrap())
+    }
+
+    fn result_json(&self, mut result: Value) -> String {
+        if !self.words {
+            if let Some(result) = result.as_object_mut() {
+                result.remove("result");
+            }
+        }
+
+        result.to_string()
+    }
+}
+
+/// Speaks the vosk-server protocol: an optional config message, binary
+/// 16-bit little-endian audio frames each answered with a partial or final
+/// result, and `{"eof": 1}` answered with the final result.
+pub fn handle(mut ws: WebSocket<TcpStream>, model: Arc<VoskModel>, sample_rate: f32) -> tungstenite::Result<()> {
+    let mut conn = Connection::new(model, sample_rate);
+
+    loop {
+        let response = match ws.read_message()? {
+            Message::Binary(data) => conn.accept(&data),
+            Message::Text(text) => {
+                let message: ControlMessage = match serde_json::from_str(&text) {
+                    Ok(message) => message,
+                    Err(err) => {
+                        let error = format!("invalid message: {}", err);
+                        ws.write_message(Message::Text(json!({ "error": error }).to_string()))?;
+                        continue;
+                    }
+                };
+
+                if let Some(config) = message.config {
+                    match conn.configure(config) {
+                        Ok(Some(flushed)) => ws.write_message(Message::Text(flushed))?,
+                        Ok(None) => {}
+                        Err(err) => {
+                            ws.write_message(Message::Text(json!({ "error": err }).to_string()))?;
+                            continue;
+                        }
+                    }
+                }
+
+                if message.eof.is_some() {
+                    ws.write_message(Message::Text(conn.finish()))?;
+                    ws.close(None)?;
+                    break;
+                }
+
+                continue;
+            }
+            Message::Close(_) => break,
+            _ => continue,
+        };
+
+        ws.write_message(Message::Text(response))?;
+    }
+
+    // Let the close handshake complete.
+    while ws.read_message().is_ok() {}
+
+    Ok(())
+}
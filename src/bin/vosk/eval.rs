@@ -0,0 +1,82 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+
+use clap::ArgMatches;
+use vosk::{Corpus, CorpusReport, VoskError};
+
+use crate::{error, load_model, session_config, EXIT_FAILURE, EXIT_MODEL, EXIT_USAGE};
+
+pub fn eval(args: &ArgMatches) -> i32 {
+    let worst = match args.value_of("worst").unwrap().parse::<usize>() {
+        Ok(worst) => worst,
+        Err(_) => {
+            error("invalid number of worst files");
+            return EXIT_USAGE;
+        }
+    };
+    let max_wer = match args.value_of("max-wer").map(str::parse::<f32>) {
+        Some(Ok(max)) if max >= 0.0 => Some(max / 100.0),
+        Some(_) => {
+            error("invalid maximum WER");
+            return EXIT_USAGE;
+        }
+        None => None,
+    };
+
+    let dir = args.value_of("CORPUS").unwrap();
+    let corpus = match Corpus::load(dir) {
+        Ok(corpus) if corpus.is_empty() => {
+            error(format!("{}: no audio with reference transcripts", dir));
+            return EXIT_USAGE;
+        }
+        Ok(corpus) => corpus,
+        Err(err) => {
+            error(format!("{}: {}", dir, err));
+            return EXIT_USAGE;
+        }
+    };
+
+    let model = match load_model(args) {
+        Some(model) => model,
+        None => return EXIT_MODEL,
+    };
+
+    // The sample rate is taken from each file.
+    let cfg = match session_config(args, 16000.0) {
+        Some(cfg) => cfg,
+        None => return EXIT_MODEL,
+    };
+    let report = corpus.evaluate(&model, &cfg);
+
+    if let Some(path) = args.value_of("report") {
+        if let Err(err) = write_report(&report, worst, path) {
+            error(format!("{}: {}", path, err));
+            return EXIT_FAILURE;
+        }
+    }
+
+    if let Err(err) = report.write_summary(worst, &mut io::stdout().lock()) {
+        error(err);
+        return EXIT_FAILURE;
+    }
+
+    if !report.failures.is_empty() {
+        return EXIT_FAILURE;
+    }
+
+    match max_wer {
+        Some(max) if report.wer() > max => {
+            error(format!("WER {:.2}% exceeds the maximum of {:.2}%", report.wer() * 100.0, max * 100.0));
+            EXIT_FAILURE
+        }
+        _ => 0,
+    }
+}
+
+fn write_report(report: &CorpusReport, worst: usize, path: &str) -> Result<(), VoskError> {
+    let mut out = BufWriter::new(File::create(path)?);
+    report.write_json(worst, &mut out)?;
+    out.flush()?;
+
+    Ok(())
+}
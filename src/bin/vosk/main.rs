@@ -0,0 +1,306 @@
+use std::ffi::OsString;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::Arc;
+
+use clap::{App, AppSettings, Arg, ArgMatches, SubCommand};
+use vosk::{OutputFormat, SpeakerModel, Transcript, VoskModel, VoskSessionConfig};
+
+mod eval;
+mod stream;
+
+const EXIT_FAILURE: i32 = 1;
+const EXIT_USAGE: i32 = 2;
+const EXIT_MODEL: i32 = 3;
+
+fn main() {
+    let app = App::new("vosk")
+        .version(env!("CARGO_PKG_VERSION"))
+        .about("Offline speech recognition with Vosk")
+        .setting(AppSettings::SubcommandRequiredElseHelp)
+        .setting(AppSettings::VersionlessSubcommands)
+        .subcommand(
+            SubCommand::with_name("transcribe")
+                .about("Transcribes audio files")
+                .args(&session_args())
+                .arg(sample_rate_arg())
+                .arg(Arg::with_name("format")
+                    .short("f")
+                    .long("format")
+                    .takes_value(true)
+                    .possible_values(&["text", "json", "srt", "vtt", "ctm"])
+                    .default_value("text")
+                    .help("Output format"))
+                .arg(Arg::with_name("output-dir")
+                    .short("o")
+                    .long("output-dir")
+                    .takes_value(true)
+                    .help("Write one <file>.<format> per input into this directory instead of stdout"))
+                .arg(Arg::with_name("FILES")
+                    .required(true)
+                    .multiple(true)
+                    .help("WAV files to transcribe")),
+        )
+        .subcommand(
+            SubCommand::with_name("stream")
+                .about("Transcribes raw PCM from stdin, printing partial and final results as JSON lines")
+                .args(&session_args())
+                .arg(sample_rate_arg())
+                .arg(Arg::with_name("encoding")
+                    .short("e")
+                    .long("encoding")
+                    .takes_value(true)
+                    .possible_values(&["s16le", "s16be", "f32le", "u8"])
+                    .default_value("s16le")
+                    .help("Sample format of the input"))
+                .arg(Arg::with_name("channels")
+                    .short("c")
+                    .long("channels")
+                    .takes_value(true)
+                    .default_value("1")
+                    .help("Number of interleaved channels, mixed down to mono"))
+                .arg(Arg::with_name("chunk-ms")
+                    .long("chunk-ms")
+                    .takes_value(true)
+                    .default_value("100")
+                    .help("Amount of audio fed to the recognizer at once, in milliseconds")),
+        )
+        .subcommand(
+            SubCommand::with_name("eval")
+                .about("Scores a model against a corpus of WAV files and reference transcripts")
+                .args(&session_args())
+                .arg(Arg::with_name("report")
+                    .long("report")
+                    .takes_value(true)
+                    .help("Write the full report as JSON to this file"))
+                .arg(Arg::with_name("worst")
+                    .long("worst")
+                    .takes_value(true)
+                    .default_value("10")
+                    .help("Number of worst files to show"))
+                .arg(Arg::with_name("max-wer")
+                    .long("max-wer")
+                    .takes_value(true)
+                    .help("Exit with an error when the corpus WER, in percent, is higher"))
+                .arg(Arg::with_name("CORPUS")
+                    .required(true)
+                    .help("Directory of *.wav with matching *.txt, or a Kaldi directory with wav.scp and text")),
+        );
+
+    let matches = match app.get_matches_safe() {
+        Ok(matches) => matches,
+        Err(err) if err.use_stderr() => {
+            eprintln!("{}", err.message);
+            process::exit(EXIT_USAGE);
+        }
+        Err(err) => {
+            println!("{}", err.message);
+            process::exit(0);
+        }
+    };
+
+    let code = match matches.subcommand() {
+        ("transcribe", Some(args)) => transcribe(args),
+        ("stream", Some(args)) => stream::stream(args),
+        ("eval", Some(args)) => eval::eval(args),
+        _ => EXIT_USAGE,
+    };
+
+    process::exit(code);
+}
+
+/// Options shared by all subcommands that create a session.
+fn session_args() -> Vec<Arg<'static, 'static>> {
+    vec![
+        Arg::with_name("model")
+            .short("m")
+            .long("model")
+            .takes_value(true)
+            .env("VOSK_MODEL")
+            .required(true)
+            .help("Model directory or archive"),
+        Arg::with_name("grammar")
+            .short("g")
+            .long("grammar")
+            .takes_value(true)
+            .conflicts_with("speaker-model")
+            .help("Phrases to restrict recognition to, as a JSON list or comma separated"),
+        Arg::with_name("speaker-model")
+            .short("s")
+            .long("speaker-model")
+            .takes_value(true)
+            .help("Speaker model directory, adds x-vectors to the JSON output"),
+    ]
+}
+
+/// Not taken by `eval`, which decodes every file at its own rate.
+fn sample_rate_arg() -> Arg<'static, 'static> {
+    Arg::with_name("sample-rate")
+        .short("r")
+        .long("sample-rate")
+        .takes_value(true)
+        .help("Sample rate of the audio, defaults to the WAV header or 16000 for stream")
+}
+
+fn error<E: std::fmt::Display>(err: E) {
+    eprintln!("vosk: error: {}", err);
+}
+
+fn load_model(args: &ArgMatches) -> Option<VoskModel> {
+    let path = Path::new(args.value_of("model").unwrap());
+
+    #[cfg(feature = "archive")]
+    let result = if path.is_file() {
+        VoskModel::from_archive(path)
+    } else {
+        VoskModel::try_new(path)
+    };
+
+    #[cfg(not(feature = "archive"))]
+    let result = VoskModel::try_new(path);
+
+    match result {
+        Ok(model) => Some(model),
+        Err(err) => {
+            error(err);
+            None
+        }
+    }
+}
+
+fn sample_rate(args: &ArgMatches) -> Result<Option<f32>, String> {
+    match args.value_of("sample-rate") {
+        Some(rate) => match rate.parse::<f32>() {
+            Ok(rate) if rate > 0.0 => Ok(Some(rate)),
+            _ => Err(format!("invalid sample rate {:?}", rate)),
+        },
+        None => Ok(None),
+    }
+}
+
+fn grammar(args: &ArgMatches) -> Option<String> {
+    let grammar = args.value_of("grammar")?.trim();
+    if grammar.starts_with('[') {
+        return Some(grammar.to_string());
+    }
+
+    let phrases: Vec<&str> = grammar.split(',').map(str::trim).filter(|p| !p.is_empty()).collect();
+
+    Some(serde_json::to_string(&phrases).unwrap())
+}
+
+/// The session settings from the arguments; `None` when the speaker model
+/// cannot be loaded, which has been reported.
+fn session_config(args: &ArgMatches, freq: f32) -> Option<VoskSessionConfig> {
+    let mut builder = VoskSessionConfig::builder();
+    builder.sampling_freq(freq);
+
+    if let Some(grammar) = grammar(args) {
+        builder.grammar(grammar);
+    }
+
+    if let Some(spk_root) = args.value_of("speaker-model") {
+        match SpeakerModel::try_new(Path::new(spk_root)) {
+            Ok(spk_model) => builder.speaker_model(Arc::new(spk_model)),
+            Err(err) => {
+                error(err);
+                return None;
+            }
+        };
+    }
+
+    Some(builder.finish())
+}
+
+fn transcribe(args: &ArgMatches) -> i32 {
+    let format: OutputFormat = args.value_of("format").unwrap().parse().unwrap();
+    let rate = match sample_rate(args) {
+        Ok(rate) => rate,
+        Err(err) => {
+            error(err);
+            return EXIT_USAGE;
+        }
+    };
+    let output_dir = args.value_of("output-dir").map(PathBuf::from);
+
+    let model = match load_model(args) {
+        Some(model) => model,
+        None => return EXIT_MODEL,
+    };
+
+    // Loaded once, the sample rate is set per file.
+    let mut cfg = match session_config(args, 16000.0) {
+        Some(cfg) => cfg,
+        None => return EXIT_MODEL,
+    };
+
+    let stdout = io::stdout();
+    let mut code = 0;
+
+    for file in args.values_of("FILES").unwrap() {
+        let audio = match vosk::read_wav(file) {
+            Ok(audio) => audio,
+            Err(err) => {
+                error(format!("{}: {}", file, err));
+                code = EXIT_FAILURE;
+                continue;
+            }
+        };
+
+        cfg.set_freq(rate.unwrap_or(audio.sample_rate as f32));
+        let id = Path::new(file).file_stem().map_or(file.into(), |stem| stem.to_string_lossy());
+        let transcript = Transcript::new(id, model.transcribe(cfg.clone(), &audio.samples));
+
+        let written = match &output_dir {
+            Some(dir) => write_to_dir(&transcript, dir, Path::new(file), format),
+            None => transcript.write(format, &mut stdout.lock()),
+        };
+
+        if let Err(err) = written {
+            error(format!("{}: {}", file, err));
+            code = EXIT_FAILURE;
+        }
+    }
+
+    if let Err(err) = stdout.lock().flush() {
+        error(err);
+        code = EXIT_FAILURE;
+    }
+
+    code
+}
+
+fn write_to_dir(transcript: &Transcript, dir: &Path, input: &Path, format: OutputFormat) -> io::Result<()> {
+    fs::create_dir_all(dir)?;
+
+    let path = dir.join(output_name(input, format));
+    let mut file = io::BufWriter::new(fs::File::create(path)?);
+
+    transcript.write(format, &mut file)?;
+    file.flush()
+}
+
+/// Appends the extension rather than using `with_extension`, which would
+/// cut "talk.part1" down to "talk".
+fn output_name(input: &Path, format: OutputFormat) -> OsString {
+    let mut name = input.file_stem().unwrap_or(input.as_os_str()).to_os_string();
+    name.push(".");
+    name.push(format.extension());
+
+    name
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn output_names() {
+        assert_eq!(output_name(Path::new("talk.wav"), OutputFormat::Srt), "talk.srt");
+        assert_eq!(output_name(Path::new("in/talk.v2.wav"), OutputFormat::Text), "talk.v2.txt");
+        assert_eq!(output_name(Path::new("talk"), OutputFormat::Json), "talk.json");
+        assert_eq!(output_name(Path::new(".hidden.wav"), OutputFormat::Vtt), ".hidden.vtt");
+    }
+}
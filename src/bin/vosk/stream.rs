@@ -0,0 +1,165 @@
+use std::io::{self, Read, Write};
+use std::str::FromStr;
+
+use clap::ArgMatches;
+use serde::Serialize;
+
+use crate::{error, load_model, sample_rate, session_config, EXIT_FAILURE, EXIT_MODEL, EXIT_USAGE};
+
+const DEFAULT_SAMPLE_RATE: f32 = 16000.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Encoding {
+    S16Le,
+    S16Be,
+    F32Le,
+    U8,
+}
+
+impl Encoding {
+    fn sample_size(self) -> usize {
+        match self {
+            Encoding::S16Le | Encoding::S16Be => 2,
+            Encoding::F32Le => 4,
+            Encoding::U8 => 1,
+        }
+    }
+
+    fn decode(self, bytes: &[u8]) -> i16 {
+        match self {
+            Encoding::S16Le => i16::from_le_bytes([bytes[0], bytes[1]]),
+            Encoding::S16Be => i16::from_be_bytes([bytes[0], bytes[1]]),
+            Encoding::F32Le => {
+                let sample = f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+                (sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16
+            }
+            Encoding::U8 => ((bytes[0] as i16) - 128) << 8,
+        }
+    }
+}
+
+impl FromStr for Encoding {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "s16le" => Ok(Encoding::S16Le),
+            "s16be" => Ok(Encoding::S16Be),
+            "f32le" => Ok(Encoding::F32Le),
+            "u8" => Ok(Encoding::U8),
+            _ => Err(format!("unknown sample format {:?}", s)),
+        }
+    }
+}
+
+pub fn stream(args: &ArgMatches) -> i32 {
+    let encoding: Encoding = args.value_of("encoding").unwrap().parse().unwrap();
+    let channels = match args.value_of("channels").unwrap().parse::<usize>() {
+        Ok(channels) if channels > 0 => channels,
+        _ => {
+            error("invalid channel count");
+            return EXIT_USAGE;
+        }
+    };
+    let chunk_ms = match args.value_of("chunk-ms").unwrap().parse::<usize>() {
+        Ok(ms) if ms > 0 => ms,
+        _ => {
+            error("invalid chunk duration");
+            return EXIT_USAGE;
+        }
+    };
+    let rate = match sample_rate(args) {
+        Ok(rate) => rate.unwrap_or(DEFAULT_SAMPLE_RATE),
+        Err(err) => {
+            error(err);
+            return EXIT_USAGE;
+        }
+    };
+
+    let model = match load_model(args) {
+        Some(model) => model,
+        None => return EXIT_MODEL,
+    };
+    let cfg = match session_config(args, rate) {
+        Some(cfg) => cfg,
+        None => return EXIT_MODEL,
+    };
+    let mut sess = model.create_session(cfg);
+
+    let frame_size = encoding.sample_size() * channels;
+    let frames_per_chunk = (rate as usize * chunk_ms / 1000).max(1);
+    let mut buf = vec![0u8; frame_size * frames_per_chunk];
+    let mut samples = Vec::with_capacity(frames_per_chunk);
+    let mut last_partial = String::new();
+
+    let stdin = io::stdin();
+    let mut stdin = stdin.lock();
+    let stdout = io::stdout();
+    let mut stdout = stdout.lock();
+
+    loop {
+        let len = match fill(&mut stdin, &mut buf) {
+            Ok(0) => break,
+            Ok(len) => len,
+            Err(err) => {
+                error(format!("stdin: {}", err));
+                return EXIT_FAILURE;
+            }
+        };
+
+        samples.clear();
+        samples.extend(buf[..len - len % frame_size].chunks(frame_size).map(|frame| {
+            let sum: i32 = frame
+                .chunks(encoding.sample_size())
+                .map(|s| encoding.decode(s) as i32)
+                .sum();
+
+            (sum / channels as i32) as i16
+        }));
+
+        let written = if model.feed(&mut sess, &samples) {
+            last_partial.clear();
+            print_line(&mut stdout, &model.get_result(&mut sess))
+        } else {
+            let partial = model.get_partial_result(&mut sess);
+            if partial.partial != last_partial {
+                last_partial = partial.partial.clone();
+                print_line(&mut stdout, &partial)
+            } else {
+                Ok(())
+            }
+        };
+
+        // The reader of our output went away, e.g. `| head`.
+        if written.is_err() {
+            return EXIT_FAILURE;
+        }
+    }
+
+    match print_line(&mut stdout, &model.get_final_result(sess)) {
+        Ok(()) => 0,
+        Err(_) => EXIT_FAILURE,
+    }
+}
+
+/// Reads until `buf` is full or the input ends.
+fn fill<R: Read>(reader: &mut R, buf: &mut [u8]) -> io::Result<usize> {
+    let mut len = 0;
+
+    while len < buf.len() {
+        match reader.read(&mut buf[len..]) {
+            Ok(0) => break,
+            Ok(n) => len += n,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+            Err(err) => return Err(err),
+        }
+    }
+
+    Ok(len)
+}
+
+fn print_line<W: Write, T: Serialize>(out: &mut W, value: &T) -> io::Result<()> {
+    serde_json::to_writer(&mut *out, value)?;
+    writeln!(out)?;
+    out.flush()
+}
@@ -0,0 +1,120 @@
+/* automatically generated by rust-bindgen 0.54.1 */
+/* vosk-api 0.3.15, regenerate with `VOSK_UPDATE_BINDINGS=1 cargo build --features bindgen` */
+
+#[repr(C)]
+pub struct VoskModel {
+    _unused: [u8; 0],
+}
+#[repr(C)]
+pub struct VoskSpkModel {
+    _unused: [u8; 0],
+}
+#[repr(C)]
+pub struct VoskRecognizer {
+    _unused: [u8; 0],
+}
+extern "C" {
+    #[doc = " Loads model data from the file and returns the model object"]
+    pub fn vosk_model_new(model_path: *const ::std::os::raw::c_char) -> *mut VoskModel;
+}
+extern "C" {
+    #[doc = " Releases the model memory"]
+    pub fn vosk_model_free(model: *mut VoskModel);
+}
+extern "C" {
+    #[doc = " Loads speaker model data from the file and returns the model object"]
+    pub fn vosk_spk_model_new(model_path: *const ::std::os::raw::c_char) -> *mut VoskSpkModel;
+}
+extern "C" {
+    #[doc = " Releases the model memory"]
+    pub fn vosk_spk_model_free(model: *mut VoskSpkModel);
+}
+extern "C" {
+    #[doc = " Creates the recognizer object"]
+    pub fn vosk_recognizer_new(model: *mut VoskModel, sample_rate: f32) -> *mut VoskRecognizer;
+}
+extern "C" {
+    #[doc = " Creates the recognizer object with speaker recognition"]
+    pub fn vosk_recognizer_new_spk(
+        model: *mut VoskModel,
+        spk_model: *mut VoskSpkModel,
+        sample_rate: f32,
+    ) -> *mut VoskRecognizer;
+}
+extern "C" {
+    #[doc = " Creates the recognizer object with the phrase list"]
+    pub fn vosk_recognizer_new_grm(
+        model: *mut VoskModel,
+        sample_rate: f32,
+        grammar: *const ::std::os::raw::c_char,
+    ) -> *mut VoskRecognizer;
+}
+extern "C" {
+    #[doc = " Accept voice data"]
+    pub fn vosk_recognizer_accept_waveform(
+        recognizer: *mut VoskRecognizer,
+        data: *const ::std::os::raw::c_char,
+        length: ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    #[doc = " Same as above but the version with the short data for language bindings where you have"]
+    #[doc = "  audio as array of shorts"]
+    pub fn vosk_recognizer_accept_waveform_s(
+        recognizer: *mut VoskRecognizer,
+        data: *const ::std::os::raw::c_short,
+        length: ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    #[doc = " Same as above but the version with the float data for language bindings where you have"]
+    #[doc = "  audio as array of floats"]
+    pub fn vosk_recognizer_accept_waveform_f(
+        recognizer: *mut VoskRecognizer,
+        data: *const f32,
+        length: ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    #[doc = " Returns speech recognition result"]
+    pub fn vosk_recognizer_result(recognizer: *mut VoskRecognizer) -> *const ::std::os::raw::c_char;
+}
+extern "C" {
+    #[doc = " Returns partial speech recognition"]
+    pub fn vosk_recognizer_partial_result(
+        recognizer: *mut VoskRecognizer,
+    ) -> *const ::std::os::raw::c_char;
+}
+extern "C" {
+    #[doc = " Returns speech recognition result. Same as result, but doesn't wait for silence"]
+    #[doc = "  You usually call it in the end of the stream to get final bits of audio. It"]
+    #[doc = "  flushes the feature pipeline, so all remaining audio chunks got processed."]
+    pub fn vosk_recognizer_final_result(
+        recognizer: *mut VoskRecognizer,
+    ) -> *const ::std::os::raw::c_char;
+}
+extern "C" {
+    #[doc = " Releases recognizer object"]
+    pub fn vosk_recognizer_free(recognizer: *mut VoskRecognizer);
+}
+extern "C" {
+    #[doc = " Set log level for Kaldi messages"]
+    #[doc = ""]
+    #[doc = "  @param log_level the level"]
+    #[doc = "     0 - default value to print info and error messages but no debug"]
+    #[doc = "     less than 0 - don't print info messages"]
+    #[doc = "     greather than 0 - more verbose mode"]
+    pub fn vosk_set_log_level(log_level: ::std::os::raw::c_int);
+}
+extern "C" {
+    #[doc = "  Init, automatically select a CUDA device and allow multithreading."]
+    #[doc = "  Must be called once from the main thread."]
+    #[doc = "  Has no effect if HAVE_CUDA flag is not set."]
+    pub fn vosk_gpu_init();
+}
+extern "C" {
+    #[doc = "  Init CUDA device in a multi-threaded environment."]
+    #[doc = "  Must be called for each thread."]
+    #[doc = "  Has no effect if HAVE_CUDA flag is not set."]
+    pub fn vosk_gpu_thread_init();
+}
@@ -0,0 +1,252 @@
+use crate::model::VoskModel;
+use crate::session::{VoskSession, VoskSessionConfig};
+use crate::transcription::TranscriptionResult;
+
+const UNKNOWN_WORD: &str = "[unk]";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CommandMatch<T> {
+    Matched {
+        intent: T,
+        phrase: String,
+        confidence: f32,
+    },
+    NoMatch,
+}
+
+impl<T> CommandMatch<T> {
+    #[inline]
+    pub fn is_match(&self) -> bool {
+        matches!(self, CommandMatch::Matched { .. })
+    }
+
+    #[inline]
+    pub fn intent(&self) -> Option<&T> {
+        match self {
+            CommandMatch::Matched { intent, .. } => Some(intent),
+            CommandMatch::NoMatch => None,
+        }
+    }
+}
+
+struct CommandPhrase<T> {
+    phrase: String,
+    intent: T,
+}
+
+pub struct CommandRecognizerBuilder<T> {
+    phrases: Vec<CommandPhrase<T>>,
+    min_confidence: f32,
+}
+
+impl<T: Clone> CommandRecognizerBuilder<T> {
+    fn new() -> Self {
+        CommandRecognizerBuilder {
+            phrases: Vec::new(),
+            min_confidence: 0.0,
+        }
+    }
+
+    /// Registers `phrases` (a command and its synonyms) as triggers for `intent`.
+    pub fn command<P: AsRef<str>>(&mut self, intent: T, phrases: &[P]) -> &mut Self {
+        for phrase in phrases {
+            let phrase = normalize(phrase.as_ref());
+            if phrase.is_empty() {
+                continue;
+            }
+
+            if let Some(existing) = self.phrases.iter_mut().find(|p| p.phrase == phrase) {
+                existing.intent = intent.clone();
+            } else {
+                self.phrases.push(CommandPhrase { phrase, intent: intent.clone() });
+            }
+        }
+
+        self
+    }
+
+    /// Matches with a mean word confidence below `conf` are reported as `NoMatch`.
+    pub fn min_confidence(&mut self, conf: f32) -> &mut Self {
+        self.min_confidence = conf;
+        self
+    }
+
+    pub fn finish(&mut self) -> CommandRecognizer<T> {
+        CommandRecognizer {
+            phrases: core::mem::take(&mut self.phrases),
+            min_confidence: self.min_confidence,
+        }
+    }
+}
+
+/// Restricts a session to a fixed set of command phrases and maps the
+/// recognized text back to user supplied intents.
+pub struct CommandRecognizer<T> {
+    phrases: Vec<CommandPhrase<T>>,
+    min_confidence: f32,
+}
+
+impl<T: Clone> CommandRecognizer<T> {
+    pub fn builder() -> CommandRecognizerBuilder<T> {
+        CommandRecognizerBuilder::new()
+    }
+
+    /// JSON grammar accepted by `VoskSessionConfigBuilder::grammar`.
+    pub fn grammar(&self) -> String {
+        let mut phrases: Vec<&str> = self.phrases.iter().map(|p| p.phrase.as_str()).collect();
+        phrases.push(UNKNOWN_WORD);
+
+        serde_json::to_string(&phrases).unwrap()
+    }
+
+    pub fn session_config(&self, freq: f32) -> VoskSessionConfig {
+        VoskSessionConfig::builder()
+            .sampling_freq(freq)
+            .grammar(self.grammar())
+            .finish()
+    }
+
+    #[inline]
+    pub fn create_session(&self, model: &VoskModel, freq: f32) -> VoskSession {
+        model.create_session(self.session_config(freq))
+    }
+
+    pub fn recognize(&self, result: &TranscriptionResult) -> CommandMatch<T> {
+        let text = normalize(&result.text);
+
+        let phrase = match self.phrases.iter().find(|p| p.phrase == text) {
+            Some(phrase) => phrase,
+            None => return CommandMatch::NoMatch,
+        };
+
+        let confidence = if result.result.is_empty() {
+            1.0
+        } else {
+            result.result.iter().map(|w| w.conf).sum::<f32>() / result.result.len() as f32
+        };
+
+        if confidence < self.min_confidence {
+            return CommandMatch::NoMatch;
+        }
+
+        CommandMatch::Matched {
+            intent: phrase.intent.clone(),
+            phrase: phrase.phrase.clone(),
+            confidence,
+        }
+    }
+}
+
+fn normalize(text: &str) -> String {
+    text.split_whitespace()
+        .map(|w| w.to_lowercase())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::recognizer::{FakeRecognizer, Recognizer};
+    use crate::transcription::TranscriptionWord;
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum Intent {
+        Lights,
+        Stop,
+    }
+
+    fn commands() -> CommandRecognizer<Intent> {
+        CommandRecognizer::builder()
+            .command(Intent::Lights, &["Turn on the  lights", "lights on"])
+            .command(Intent::Stop, &["stop", "halt", ""])
+            .min_confidence(0.5)
+            .finish()
+    }
+
+    fn result(text: &str, conf: f32) -> TranscriptionResult {
+        TranscriptionResult {
+            text: text.to_string(),
+            result: text
+                .split_whitespace()
+                .map(|word| TranscriptionWord {
+                    conf,
+                    start: 0.0,
+                    end: 0.0,
+                    word: word.to_string(),
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn grammar_lists_normalized_phrases() {
+        assert_eq!(commands().grammar(), r#"["turn on the lights","lights on","stop","halt","[unk]"]"#);
+    }
+
+    #[test]
+    fn later_commands_take_over_phrases() {
+        let commands = CommandRecognizer::builder()
+            .command(Intent::Lights, &["go"])
+            .command(Intent::Stop, &["GO"])
+            .finish();
+
+        assert_eq!(commands.grammar(), r#"["go","[unk]"]"#);
+        assert_eq!(commands.recognize(&result("go", 1.0)).intent(), Some(&Intent::Stop));
+    }
+
+    #[test]
+    fn recognize_fake_results() {
+        let mut fake = FakeRecognizer::builder()
+            .result(100, result("lights on", 0.9))
+            .result(200, result("[unk]", 0.9))
+            .result(300, result("halt", 0.3))
+            .final_result(result("TURN  on the lights", 0.7))
+            .finish();
+
+        let commands = commands();
+        let matches: Vec<_> = fake
+            .transcribe(&[0; 400], 100)
+            .iter()
+            .map(|r| commands.recognize(r))
+            .collect();
+
+        assert_eq!(
+            matches,
+            vec![
+                CommandMatch::Matched {
+                    intent: Intent::Lights,
+                    phrase: "lights on".to_string(),
+                    confidence: 0.9,
+                },
+                CommandMatch::NoMatch,
+                // Below the minimum confidence.
+                CommandMatch::NoMatch,
+                CommandMatch::Matched {
+                    intent: Intent::Lights,
+                    phrase: "turn on the lights".to_string(),
+                    confidence: 0.7,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn results_without_words_are_certain() {
+        let result = TranscriptionResult {
+            text: "stop".to_string(),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            commands().recognize(&result),
+            CommandMatch::Matched {
+                intent: Intent::Stop,
+                phrase: "stop".to_string(),
+                confidence: 1.0,
+            }
+        );
+        assert!(!commands().recognize(&TranscriptionResult::default()).is_match());
+    }
+}
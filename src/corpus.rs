@@ -0,0 +1,437 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::VoskError;
+use crate::model::VoskModel;
+use crate::session::VoskSessionConfig;
+use crate::wer::{CorpusEvaluation, Evaluation};
+
+#[derive(Debug, Clone)]
+pub struct CorpusEntry {
+    pub id: String,
+    pub audio: PathBuf,
+    pub reference: String,
+}
+
+/// Recordings paired with their reference transcripts.
+#[derive(Debug, Clone, Default)]
+pub struct Corpus {
+    entries: Vec<CorpusEntry>,
+}
+
+impl Corpus {
+    /// Reads a Kaldi data directory if `dir` has a `wav.scp`, otherwise
+    /// pairs every `*.wav` with the `*.txt` of the same name.
+    pub fn load<P: AsRef<Path>>(dir: P) -> Result<Self, VoskError> {
+        if dir.as_ref().join("wav.scp").is_file() {
+            Self::from_kaldi(dir)
+        } else {
+            Self::from_dir(dir)
+        }
+    }
+
+    /// WAV files without a matching `.txt` are skipped.
+    pub fn from_dir<P: AsRef<Path>>(dir: P) -> Result<Self, VoskError> {
+        let mut entries = Vec::new();
+
+        for entry in fs::read_dir(dir)? {
+            let audio = entry?.path();
+            if !audio.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("wav")) {
+                continue;
+            }
+
+            let reference = audio.with_extension("txt");
+            if !reference.is_file() {
+                continue;
+            }
+
+            entries.push(CorpusEntry {
+                id: audio.file_stem().unwrap().to_string_lossy().into_owned(),
+                reference: fs::read_to_string(reference)?.trim().to_string(),
+                audio,
+            });
+        }
+
+        entries.sort_by(|a, b| a.id.cmp(&b.id));
+
+        Ok(Self { entries })
+    }
+
+    /// Reads `wav.scp` and `text`. Relative audio paths are tried against the
+    /// working directory, as Kaldi does, then against `dir`; utterances
+    /// missing from `text` are skipped.
+    pub fn from_kaldi<P: AsRef<Path>>(dir: P) -> Result<Self, VoskError> {
+        let dir = dir.as_ref();
+        let text = fs::read_to_string(dir.join("text"))?;
+        let mut references: HashMap<&str, &str> = text.lines().filter_map(split_id).collect();
+
+        let mut entries = Vec::new();
+
+        for (line_no, line) in fs::read_to_string(dir.join("wav.scp"))?.lines().enumerate() {
+            let (id, path) = match split_id(line) {
+                Some(pair) => pair,
+                None => continue,
+            };
+
+            if path.ends_with('|') {
+                return Err(VoskError::Corpus(format!(
+                    "wav.scp:{}: piped commands are not supported",
+                    line_no + 1
+                )));
+            }
+
+            let reference = match references.remove(id) {
+                Some(reference) => reference,
+                None => continue,
+            };
+
+            let mut audio = PathBuf::from(path);
+            if audio.is_relative() && !audio.exists() {
+                audio = dir.join(audio);
+            }
+
+            entries.push(CorpusEntry {
+                id: id.to_string(),
+                audio,
+                reference: reference.to_string(),
+            });
+        }
+
+        Ok(Self { entries })
+    }
+
+    #[inline]
+    pub fn entries(&self) -> &[CorpusEntry] {
+        &self.entries
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Decodes every entry with `cfg`, its sample rate replaced by that of
+    /// each file. Files that fail to read are listed in the report instead.
+    pub fn evaluate(&self, model: &VoskModel, cfg: &VoskSessionConfig) -> CorpusReport {
+        let mut report = CorpusReport::default();
+
+        for entry in &self.entries {
+            let audio = match crate::audio::read_wav(&entry.audio) {
+                Ok(audio) => audio,
+                Err(err) => {
+                    report.failures.push(FileFailure {
+                        id: entry.id.clone(),
+                        error: err.to_string(),
+                    });
+                    continue;
+                }
+            };
+
+            let mut cfg = cfg.clone();
+            cfg.set_freq(audio.sample_rate as f32);
+
+            let started = Instant::now();
+            let results = model.transcribe(cfg, &audio.samples);
+            let decode_seconds = started.elapsed().as_secs_f32();
+
+            let hypothesis: Vec<&str> = results.iter().map(|r| r.text.as_str()).collect();
+            let evaluation = Evaluation::from_results(&entry.reference, &results);
+            report.total.add(&evaluation);
+
+            report.files.push(FileReport {
+                id: entry.id.clone(),
+                audio: entry.audio.clone(),
+                reference: entry.reference.clone(),
+                hypothesis: hypothesis.join(" "),
+                wer: evaluation.wer(),
+                cer: evaluation.cer(),
+                audio_seconds: audio.duration(),
+                decode_seconds,
+                evaluation,
+            });
+        }
+
+        report
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FileReport {
+    pub id: String,
+    pub audio: PathBuf,
+    pub reference: String,
+    pub hypothesis: String,
+    pub wer: f32,
+    pub cer: f32,
+    pub audio_seconds: f32,
+    pub decode_seconds: f32,
+    pub evaluation: Evaluation,
+}
+
+impl FileReport {
+    /// Real-time factor, decoding time per second of audio.
+    pub fn rtf(&self) -> f32 {
+        self.decode_seconds / self.audio_seconds.max(f32::EPSILON)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FileFailure {
+    pub id: String,
+    pub error: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct CorpusReport {
+    pub files: Vec<FileReport>,
+    pub failures: Vec<FileFailure>,
+    pub total: CorpusEvaluation,
+}
+
+impl CorpusReport {
+    #[inline]
+    pub fn wer(&self) -> f32 {
+        self.total.wer()
+    }
+
+    #[inline]
+    pub fn cer(&self) -> f32 {
+        self.total.cer()
+    }
+
+    pub fn audio_seconds(&self) -> f32 {
+        self.files.iter().map(|f| f.audio_seconds).sum()
+    }
+
+    pub fn decode_seconds(&self) -> f32 {
+        self.files.iter().map(|f| f.decode_seconds).sum()
+    }
+
+    pub fn rtf(&self) -> f32 {
+        self.decode_seconds() / self.audio_seconds().max(f32::EPSILON)
+    }
+
+    /// The `n` files with the highest WER, most word errors first on ties.
+    pub fn worst(&self, n: usize) -> Vec<&FileReport> {
+        let mut files: Vec<&FileReport> = self.files.iter().collect();
+        files.sort_by(|a, b| {
+            b.wer
+                .partial_cmp(&a.wer)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then(b.evaluation.words.errors().cmp(&a.evaluation.words.errors()))
+        });
+        files.truncate(n);
+
+        files
+    }
+
+    /// Writes the whole report, including the worst `worst` file ids and the
+    /// corpus totals, as pretty-printed JSON.
+    pub fn write_json<W: Write>(&self, worst: usize, out: &mut W) -> Result<(), VoskError> {
+        let worst: Vec<&str> = self.worst(worst).iter().map(|f| f.id.as_str()).collect();
+        let report = serde_json::json!({
+            "wer": self.wer(),
+            "cer": self.cer(),
+            "rtf": self.rtf(),
+            "audio_seconds": self.audio_seconds(),
+            "decode_seconds": self.decode_seconds(),
+            "total": self.total,
+            "worst": worst,
+            "files": self.files,
+            "failures": self.failures,
+        });
+
+        serde_json::to_writer_pretty(&mut *out, &report)?;
+        writeln!(out)?;
+
+        Ok(())
+    }
+
+    /// Writes totals and the worst `worst` files with their aligned diffs.
+    pub fn write_summary<W: Write>(&self, worst: usize, out: &mut W) -> io::Result<()> {
+        let words = &self.total.words;
+
+        writeln!(out, "files:  {} decoded, {} failed", self.files.len(), self.failures.len())?;
+        writeln!(
+            out,
+            "WER:    {:.2}% ({} errors / {} words: {} sub, {} ins, {} del)",
+            self.wer() * 100.0,
+            words.errors(),
+            words.reference_len,
+            words.substitutions,
+            words.insertions,
+            words.deletions
+        )?;
+        writeln!(out, "CER:    {:.2}%", self.cer() * 100.0)?;
+        writeln!(out, "SER:    {:.2}%", self.total.sentence_error_rate() * 100.0)?;
+        writeln!(
+            out,
+            "RTF:    {:.3} ({:.1}s decoding {:.1}s of audio)",
+            self.rtf(),
+            self.decode_seconds(),
+            self.audio_seconds()
+        )?;
+
+        for failure in &self.failures {
+            writeln!(out, "failed: {}: {}", failure.id, failure.error)?;
+        }
+
+        for file in self.worst(worst).into_iter().filter(|f| f.evaluation.words.errors() > 0) {
+            writeln!(out)?;
+            writeln!(out, "{} (WER {:.2}%)", file.id, file.wer * 100.0)?;
+            write!(out, "{}", file.evaluation.alignment)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn split_id(line: &str) -> Option<(&str, &str)> {
+    let line = line.trim();
+    if line.is_empty() {
+        return None;
+    }
+
+    match line.find(char::is_whitespace) {
+        Some(idx) => Some((&line[..idx], line[idx..].trim_start())),
+        None => Some((line, "")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(dir: &Path, name: &str, contents: &str) {
+        fs::write(dir.join(name), contents).unwrap();
+    }
+
+    fn file(id: &str, reference: &str, hypothesis: &str) -> FileReport {
+        let evaluation = Evaluation::new(reference, hypothesis);
+
+        FileReport {
+            id: id.to_string(),
+            audio: PathBuf::from(format!("{}.wav", id)),
+            reference: reference.to_string(),
+            hypothesis: hypothesis.to_string(),
+            wer: evaluation.wer(),
+            cer: evaluation.cer(),
+            audio_seconds: 1.0,
+            decode_seconds: 0.5,
+            evaluation,
+        }
+    }
+
+    #[test]
+    fn split_ids() {
+        assert_eq!(split_id("utt1 hello world"), Some(("utt1", "hello world")));
+        assert_eq!(split_id("  utt1\t  hello world  "), Some(("utt1", "hello world")));
+        assert_eq!(split_id("utt1"), Some(("utt1", "")));
+        assert_eq!(split_id("   "), None);
+    }
+
+    #[test]
+    fn kaldi_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        write(
+            dir.path(),
+            "wav.scp",
+            "b audio/b.wav\na /data/a.wav\n\nc Cargo.toml\nmissing audio/m.wav\n",
+        );
+        write(dir.path(), "text", "a hello world\nb  good morning \nc yes\n");
+
+        let corpus = Corpus::load(dir.path()).unwrap();
+        let entries: Vec<_> = corpus
+            .entries()
+            .iter()
+            .map(|e| (e.id.as_str(), e.audio.clone(), e.reference.as_str()))
+            .collect();
+
+        assert_eq!(
+            entries,
+            vec![
+                ("b", dir.path().join("audio/b.wav"), "good morning"),
+                ("a", PathBuf::from("/data/a.wav"), "hello world"),
+                // Relative paths that exist from the working directory are kept.
+                ("c", PathBuf::from("Cargo.toml"), "yes"),
+            ]
+        );
+    }
+
+    #[test]
+    fn kaldi_piped_commands() {
+        let dir = tempfile::tempdir().unwrap();
+        write(dir.path(), "wav.scp", "a a.wav\nb sox b.flac -t wav - |\n");
+        write(dir.path(), "text", "a hello\nb world\n");
+
+        match Corpus::from_kaldi(dir.path()) {
+            Err(VoskError::Corpus(msg)) => assert_eq!(msg, "wav.scp:2: piped commands are not supported"),
+            other => panic!("unexpected {:?}", other.map(|c| c.len())),
+        }
+    }
+
+    #[test]
+    fn kaldi_without_text() {
+        let dir = tempfile::tempdir().unwrap();
+        write(dir.path(), "wav.scp", "a a.wav\n");
+
+        assert!(matches!(Corpus::load(dir.path()), Err(VoskError::Io(_))));
+    }
+
+    #[test]
+    fn wav_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        write(dir.path(), "b.wav", "");
+        write(dir.path(), "b.txt", "good morning\n");
+        write(dir.path(), "a.WAV", "");
+        write(dir.path(), "a.txt", " hello world ");
+        write(dir.path(), "unreferenced.wav", "");
+        write(dir.path(), "notes.txt", "not audio");
+
+        let corpus = Corpus::load(dir.path()).unwrap();
+        let entries: Vec<_> = corpus
+            .entries()
+            .iter()
+            .map(|e| (e.id.as_str(), e.audio.clone(), e.reference.as_str()))
+            .collect();
+
+        assert_eq!(
+            entries,
+            vec![
+                ("a", dir.path().join("a.WAV"), "hello world"),
+                ("b", dir.path().join("b.wav"), "good morning"),
+            ]
+        );
+    }
+
+    #[test]
+    fn worst_files_first() {
+        let report = CorpusReport {
+            files: vec![
+                file("perfect", "a b c d", "a b c d"),
+                file("half", "a b c d", "a x c y"),
+                file("short", "a b", "a x"),
+                file("worst", "a b", "x y z"),
+                file("one", "a b c d", "a b c x"),
+            ],
+            ..Default::default()
+        };
+
+        let ids: Vec<&str> = report.worst(10).iter().map(|f| f.id.as_str()).collect();
+        // "half" and "short" tie at 50%, "half" has more errors.
+        assert_eq!(ids, vec!["worst", "half", "short", "one", "perfect"]);
+
+        let ids: Vec<&str> = report.worst(2).iter().map(|f| f.id.as_str()).collect();
+        assert_eq!(ids, vec!["worst", "half"]);
+    }
+}
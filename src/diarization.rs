@@ -0,0 +1,234 @@
+use serde::{Serialize, Deserialize};
+
+use crate::speaker::{cosine_similarity, normalize_xvector};
+use crate::transcription::{TranscriptionResult, TranscriptionWord};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Clustering {
+    /// Keep merging clusters while their centroids are at least this similar.
+    Threshold(f32),
+    /// Merge clusters until exactly this many speakers remain.
+    Speakers(usize),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DiarizedSegment {
+    pub speaker: Option<usize>,
+    pub start: f32,
+    pub end: f32,
+    pub text: String,
+    pub words: Vec<TranscriptionWord>,
+}
+
+/// Groups utterances of a speaker session into "who spoke when" by
+/// agglomerative clustering of their x-vectors.
+pub struct Diarizer {
+    clustering: Clustering,
+    min_frames: u32,
+}
+
+impl Default for Diarizer {
+    fn default() -> Self {
+        Self {
+            clustering: Clustering::Threshold(0.5),
+            min_frames: 0,
+        }
+    }
+}
+
+impl Diarizer {
+    pub fn new(clustering: Clustering) -> Self {
+        Self {
+            clustering,
+            ..Default::default()
+        }
+    }
+
+    /// Utterances whose x-vector was computed from fewer frames are left unlabeled.
+    #[inline]
+    pub fn set_min_frames(&mut self, frames: u32) {
+        self.min_frames = frames;
+    }
+
+    /// Assigns a speaker label to every x-vector. Labels are numbered in
+    /// order of first appearance.
+    pub fn cluster(&self, xvectors: &[&[f32]]) -> Vec<usize> {
+        let mut clusters: Vec<Cluster> = xvectors
+            .iter()
+            .enumerate()
+            .map(|(idx, xv)| Cluster::new(idx, xv))
+            .collect();
+
+        loop {
+            if let Clustering::Speakers(n) = self.clustering {
+                if clusters.len() <= n.max(1) {
+                    break;
+                }
+            }
+
+            if clusters.len() < 2 {
+                break;
+            }
+
+            let (mut i, mut j, mut sim) = (0, 1, f32::NEG_INFINITY);
+            for a in 0..clusters.len() {
+                for b in a + 1..clusters.len() {
+                    let s = cosine_similarity(&clusters[a].centroid, &clusters[b].centroid);
+                    if s > sim {
+                        i = a;
+                        j = b;
+                        sim = s;
+                    }
+                }
+            }
+
+            if let Clustering::Threshold(threshold) = self.clustering {
+                if sim < threshold {
+                    break;
+                }
+            }
+
+            let other = clusters.swap_remove(j);
+            clusters[i].merge(other);
+        }
+
+        clusters.sort_by_key(|c| c.members[0]);
+
+        let mut labels = vec![0; xvectors.len()];
+        for (label, cluster) in clusters.iter().enumerate() {
+            for &member in &cluster.members {
+                labels[member] = label;
+            }
+        }
+
+        labels
+    }
+
+    pub fn diarize(&self, utterances: &[TranscriptionResult]) -> Vec<DiarizedSegment> {
+        let mut indices = Vec::new();
+        let mut xvectors = Vec::new();
+
+        for (idx, utt) in utterances.iter().enumerate() {
+            if let Some(spk) = &utt.spk {
+                if spk.is_empty() || utt.spk_frames.unwrap_or(0) < self.min_frames {
+                    continue;
+                }
+
+                indices.push(idx);
+                xvectors.push(spk.as_slice());
+            }
+        }
+
+        let mut speakers = vec![None; utterances.len()];
+        for (idx, label) in indices.into_iter().zip(self.cluster(&xvectors)) {
+            speakers[idx] = Some(label);
+        }
+
+        utterances
+            .iter()
+            .zip(speakers)
+            .filter(|(utt, _)| !utt.text.is_empty())
+            .map(|(utt, speaker)| DiarizedSegment {
+                speaker,
+                start: utt.result.first().map_or(0.0, |w| w.start),
+                end: utt.result.last().map_or(0.0, |w| w.end),
+                text: utt.text.clone(),
+                words: utt.result.clone(),
+            })
+            .collect()
+    }
+}
+
+struct Cluster {
+    members: Vec<usize>,
+    sum: Vec<f32>,
+    centroid: Vec<f32>,
+}
+
+impl Cluster {
+    fn new(idx: usize, xvector: &[f32]) -> Self {
+        let sum = normalize_xvector(xvector);
+
+        Self {
+            members: vec![idx],
+            centroid: sum.clone(),
+            sum,
+        }
+    }
+
+    fn merge(&mut self, other: Cluster) {
+        self.members.extend(other.members);
+        self.members.sort_unstable();
+
+        for (a, b) in self.sum.iter_mut().zip(other.sum) {
+            *a += b;
+        }
+
+        let count = self.members.len() as f32;
+        self.centroid = self.sum.iter().map(|x| x / count).collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const XVECTORS: [&[f32]; 5] = [
+        &[1.0, 0.0, 0.0],
+        &[0.0, 1.0, 0.0],
+        &[0.9, 0.1, 0.0],
+        &[0.0, 0.0, 1.0],
+        &[0.1, 0.9, 0.0],
+    ];
+
+    #[test]
+    fn threshold_merges_similar_speakers() {
+        let diarizer = Diarizer::new(Clustering::Threshold(0.9));
+        assert_eq!(diarizer.cluster(&XVECTORS), vec![0, 1, 0, 2, 1]);
+    }
+
+    #[test]
+    fn threshold_above_every_similarity_keeps_all() {
+        let diarizer = Diarizer::new(Clustering::Threshold(1.1));
+        assert_eq!(diarizer.cluster(&XVECTORS), vec![0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn speaker_count_merges_closest_first() {
+        assert_eq!(Diarizer::new(Clustering::Speakers(3)).cluster(&XVECTORS), vec![0, 1, 0, 2, 1]);
+
+        // The third speaker is orthogonal to both others, which are slightly alike.
+        assert_eq!(Diarizer::new(Clustering::Speakers(2)).cluster(&XVECTORS), vec![0, 0, 0, 1, 0]);
+    }
+
+    #[test]
+    fn speaker_count_above_utterances() {
+        assert_eq!(Diarizer::new(Clustering::Speakers(10)).cluster(&XVECTORS), vec![0, 1, 2, 3, 4]);
+        assert_eq!(Diarizer::new(Clustering::Speakers(0)).cluster(&XVECTORS), vec![0, 0, 0, 0, 0]);
+        assert!(Diarizer::new(Clustering::Speakers(2)).cluster(&[]).is_empty());
+    }
+
+    #[test]
+    fn diarize_skips_short_and_missing_xvectors() {
+        let utterance = |text: &str, spk: Option<Vec<f32>>, frames| TranscriptionResult {
+            text: text.to_string(),
+            spk,
+            spk_frames: Some(frames),
+            ..Default::default()
+        };
+
+        let mut diarizer = Diarizer::new(Clustering::Threshold(0.9));
+        diarizer.set_min_frames(100);
+
+        let segments = diarizer.diarize(&[
+            utterance("hello", Some(vec![1.0, 0.0]), 200),
+            utterance("", Some(vec![0.0, 1.0]), 200),
+            utterance("yes", Some(vec![1.0, 0.0]), 50),
+            utterance("no", None, 200),
+            utterance("bye", Some(vec![0.0, 1.0]), 200),
+        ]);
+
+        let speakers: Vec<_> = segments.iter().map(|s| (s.text.as_str(), s.speaker)).collect();
+        assert_eq!(speakers, vec![("hello", Some(0)), ("yes", None), ("no", None), ("bye", Some(1))]);
+    }
+}
@@ -0,0 +1,207 @@
+use std::ffi::{OsStr, OsString};
+use std::os::raw::{c_char, c_int, c_short};
+use std::ptr;
+use std::sync::Mutex;
+
+use libloading::Library;
+use once_cell::sync::OnceCell;
+
+use crate::error::VoskError;
+
+#[cfg(target_os = "windows")]
+const DEFAULT_LIBRARY: &str = "libvosk.dll";
+#[cfg(target_os = "macos")]
+const DEFAULT_LIBRARY: &str = "libvosk.dylib";
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+const DEFAULT_LIBRARY: &str = "libvosk.so";
+
+#[repr(C)]
+pub struct VoskModel {
+    _unused: [u8; 0],
+}
+
+#[repr(C)]
+pub struct VoskSpkModel {
+    _unused: [u8; 0],
+}
+
+#[repr(C)]
+pub struct VoskRecognizer {
+    _unused: [u8; 0],
+}
+
+struct VoskLibrary {
+    _library: Library,
+    path: OsString,
+    model_new: unsafe extern "C" fn(*const c_char) -> *mut VoskModel,
+    model_free: unsafe extern "C" fn(*mut VoskModel),
+    spk_model_new: unsafe extern "C" fn(*const c_char) -> *mut VoskSpkModel,
+    spk_model_free: unsafe extern "C" fn(*mut VoskSpkModel),
+    recognizer_new: unsafe extern "C" fn(*mut VoskModel, f32) -> *mut VoskRecognizer,
+    recognizer_new_spk: unsafe extern "C" fn(*mut VoskModel, *mut VoskSpkModel, f32) -> *mut VoskRecognizer,
+    recognizer_new_grm: unsafe extern "C" fn(*mut VoskModel, f32, *const c_char) -> *mut VoskRecognizer,
+    recognizer_accept_waveform_s: unsafe extern "C" fn(*mut VoskRecognizer, *const c_short, c_int) -> c_int,
+    recognizer_result: unsafe extern "C" fn(*mut VoskRecognizer) -> *const c_char,
+    recognizer_partial_result: unsafe extern "C" fn(*mut VoskRecognizer) -> *const c_char,
+    recognizer_final_result: unsafe extern "C" fn(*mut VoskRecognizer) -> *const c_char,
+    recognizer_free: unsafe extern "C" fn(*mut VoskRecognizer),
+    set_log_level: Option<unsafe extern "C" fn(c_int)>,
+}
+
+static LIBRARY: OnceCell<VoskLibrary> = OnceCell::new();
+
+// Set before the library could be loaded; applied once it is.
+static PENDING_LOG_LEVEL: Mutex<Option<c_int>> = Mutex::new(None);
+
+macro_rules! symbol {
+    ($library:expr, $name:literal) => {
+        *$library
+            .get(concat!($name, "\0").as_bytes())
+            .map_err(|_| VoskError::MissingSymbol($name.to_string()))?
+    };
+}
+
+impl VoskLibrary {
+    fn open(path: &OsStr) -> Result<Self, VoskError> {
+        let library = Library::new(path)
+            .map_err(|err| VoskError::LibraryLoad(format!("{}: {}", path.to_string_lossy(), err)))?;
+
+        unsafe {
+            // Missing in older releases; setting the level is then a no-op.
+            let set_log_level: Option<unsafe extern "C" fn(c_int)> =
+                library.get(b"vosk_set_log_level\0").ok().map(|s| *s);
+
+            if let (Some(set_log_level), Some(level)) = (set_log_level, *PENDING_LOG_LEVEL.lock().unwrap()) {
+                set_log_level(level);
+            }
+
+            Ok(Self {
+                model_new: symbol!(library, "vosk_model_new"),
+                model_free: symbol!(library, "vosk_model_free"),
+                spk_model_new: symbol!(library, "vosk_spk_model_new"),
+                spk_model_free: symbol!(library, "vosk_spk_model_free"),
+                recognizer_new: symbol!(library, "vosk_recognizer_new"),
+                recognizer_new_spk: symbol!(library, "vosk_recognizer_new_spk"),
+                recognizer_new_grm: symbol!(library, "vosk_recognizer_new_grm"),
+                recognizer_accept_waveform_s: symbol!(library, "vosk_recognizer_accept_waveform_s"),
+                recognizer_result: symbol!(library, "vosk_recognizer_result"),
+                recognizer_partial_result: symbol!(library, "vosk_recognizer_partial_result"),
+                recognizer_final_result: symbol!(library, "vosk_recognizer_final_result"),
+                recognizer_free: symbol!(library, "vosk_recognizer_free"),
+                set_log_level,
+                _library: library,
+                path: path.to_os_string(),
+            })
+        }
+    }
+}
+
+/// Loads libvosk from `path`. Once a library is loaded, loading it again is a
+/// no-op and loading another one an error.
+pub fn load_library<P: AsRef<OsStr>>(path: P) -> Result<(), VoskError> {
+    let path = path.as_ref();
+    let library = LIBRARY.get_or_try_init(|| VoskLibrary::open(path))?;
+
+    if library.path != path {
+        return Err(VoskError::LibraryLoad(format!(
+            "{}: {} is already loaded",
+            path.to_string_lossy(),
+            library.path.to_string_lossy()
+        )));
+    }
+
+    Ok(())
+}
+
+#[inline]
+pub fn is_library_loaded() -> bool {
+    LIBRARY.get().is_some()
+}
+
+/// Loads libvosk from `$VOSK_LIBRARY`, or by its platform name from the
+/// dynamic linker search path, unless `load_library` was called before.
+pub(crate) fn ensure_loaded() -> Result<(), VoskError> {
+    library().map(|_| ())
+}
+
+fn library() -> Result<&'static VoskLibrary, VoskError> {
+    LIBRARY.get_or_try_init(|| {
+        let path = std::env::var_os("VOSK_LIBRARY").unwrap_or_else(|| DEFAULT_LIBRARY.into());
+
+        VoskLibrary::open(&path)
+    })
+}
+
+/// Only for functions taking a model or recognizer, which cannot exist
+/// without the library.
+#[inline]
+fn loaded() -> &'static VoskLibrary {
+    match library() {
+        Ok(library) => library,
+        Err(err) => panic!("{}", err),
+    }
+}
+
+/// Null, like a model that fails to load, when the library cannot be loaded.
+pub unsafe fn vosk_model_new(model_path: *const c_char) -> *mut VoskModel {
+    match library() {
+        Ok(library) => (library.model_new)(model_path),
+        Err(_) => ptr::null_mut(),
+    }
+}
+
+pub unsafe fn vosk_model_free(model: *mut VoskModel) {
+    (loaded().model_free)(model)
+}
+
+pub unsafe fn vosk_spk_model_new(model_path: *const c_char) -> *mut VoskSpkModel {
+    match library() {
+        Ok(library) => (library.spk_model_new)(model_path),
+        Err(_) => ptr::null_mut(),
+    }
+}
+
+pub unsafe fn vosk_spk_model_free(model: *mut VoskSpkModel) {
+    (loaded().spk_model_free)(model)
+}
+
+pub unsafe fn vosk_recognizer_new(model: *mut VoskModel, sample_rate: f32) -> *mut VoskRecognizer {
+    (loaded().recognizer_new)(model, sample_rate)
+}
+
+pub unsafe fn vosk_recognizer_new_spk(model: *mut VoskModel, spk_model: *mut VoskSpkModel, sample_rate: f32) -> *mut VoskRecognizer {
+    (loaded().recognizer_new_spk)(model, spk_model, sample_rate)
+}
+
+pub unsafe fn vosk_recognizer_new_grm(model: *mut VoskModel, sample_rate: f32, grammar: *const c_char) -> *mut VoskRecognizer {
+    (loaded().recognizer_new_grm)(model, sample_rate, grammar)
+}
+
+pub unsafe fn vosk_recognizer_accept_waveform_s(recognizer: *mut VoskRecognizer, data: *const c_short, length: c_int) -> c_int {
+    (loaded().recognizer_accept_waveform_s)(recognizer, data, length)
+}
+
+pub unsafe fn vosk_recognizer_result(recognizer: *mut VoskRecognizer) -> *const c_char {
+    (loaded().recognizer_result)(recognizer)
+}
+
+pub unsafe fn vosk_recognizer_partial_result(recognizer: *mut VoskRecognizer) -> *const c_char {
+    (loaded().recognizer_partial_result)(recognizer)
+}
+
+pub unsafe fn vosk_recognizer_final_result(recognizer: *mut VoskRecognizer) -> *const c_char {
+    (loaded().recognizer_final_result)(recognizer)
+}
+
+pub unsafe fn vosk_recognizer_free(recognizer: *mut VoskRecognizer) {
+    (loaded().recognizer_free)(recognizer)
+}
+
+/// Never loads the library itself, so a later `load_library` still picks it.
+pub unsafe fn vosk_set_log_level(log_level: c_int) {
+    *PENDING_LOG_LEVEL.lock().unwrap() = Some(log_level);
+
+    if let Some(VoskLibrary { set_log_level: Some(set_log_level), .. }) = LIBRARY.get() {
+        set_log_level(log_level)
+    }
+}
@@ -0,0 +1,59 @@
+use std::{fmt, io};
+use std::path::PathBuf;
+
+use crate::model_info::ValidationReport;
+
+#[derive(Debug)]
+pub enum VoskError {
+    Io(io::Error),
+    Json(serde_json::Error),
+    InvalidModel(ValidationReport),
+    ModelLoad(PathBuf),
+    UnknownModel(String),
+    Archive(String),
+    LibraryLoad(String),
+    MissingSymbol(String),
+    Audio(String),
+    Corpus(String),
+    Vocabulary(String),
+}
+
+impl fmt::Display for VoskError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VoskError::Io(err) => write!(f, "io error: {}", err),
+            VoskError::Json(err) => write!(f, "json error: {}", err),
+            VoskError::InvalidModel(report) => write!(f, "invalid model:\n{}", report),
+            VoskError::ModelLoad(path) => write!(f, "failed to load model from {}", path.display()),
+            VoskError::UnknownModel(name) => write!(f, "no model registered for {}", name),
+            VoskError::Archive(msg) => write!(f, "archive error: {}", msg),
+            VoskError::LibraryLoad(msg) => write!(f, "failed to load libvosk: {}", msg),
+            VoskError::MissingSymbol(name) => write!(f, "incompatible libvosk: symbol {} not found", name),
+            VoskError::Audio(msg) => write!(f, "audio error: {}", msg),
+            VoskError::Corpus(msg) => write!(f, "corpus error: {}", msg),
+            VoskError::Vocabulary(msg) => write!(f, "vocabulary error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for VoskError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            VoskError::Io(err) => Some(err),
+            VoskError::Json(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for VoskError {
+    fn from(err: io::Error) -> Self {
+        VoskError::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for VoskError {
+    fn from(err: serde_json::Error) -> Self {
+        VoskError::Json(err)
+    }
+}
@@ -0,0 +1,300 @@
+use std::fmt;
+use std::io::{self, Write};
+use std::str::FromStr;
+
+use serde::{Serialize, Deserialize};
+
+use crate::transcription::{TranscriptionResult, TranscriptionWord};
+
+const MAX_CUE_WORDS: usize = 10;
+const MAX_CUE_DURATION: f32 = 5.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OutputFormat {
+    Text,
+    Json,
+    Srt,
+    Vtt,
+    Ctm,
+}
+
+impl OutputFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            OutputFormat::Text => "txt",
+            OutputFormat::Json => "json",
+            OutputFormat::Srt => "srt",
+            OutputFormat::Vtt => "vtt",
+            OutputFormat::Ctm => "ctm",
+        }
+    }
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "text" | "txt" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            "srt" => Ok(OutputFormat::Srt),
+            "vtt" => Ok(OutputFormat::Vtt),
+            "ctm" => Ok(OutputFormat::Ctm),
+            _ => Err(format!("unknown output format {:?}, expected text, json, srt, vtt or ctm", s)),
+        }
+    }
+}
+
+impl fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            OutputFormat::Text => "text",
+            OutputFormat::Json => "json",
+            OutputFormat::Srt => "srt",
+            OutputFormat::Vtt => "vtt",
+            OutputFormat::Ctm => "ctm",
+        })
+    }
+}
+
+/// All utterances recognized in one audio file.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Transcript {
+    pub id: String,
+    pub text: String,
+    pub utterances: Vec<TranscriptionResult>,
+}
+
+impl Transcript {
+    pub fn new<S: Into<String>>(id: S, utterances: Vec<TranscriptionResult>) -> Self {
+        let text = utterances
+            .iter()
+            .map(|u| u.text.as_str())
+            .filter(|t| !t.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        Self {
+            id: id.into(),
+            text,
+            utterances,
+        }
+    }
+
+    pub fn words(&self) -> impl Iterator<Item = &TranscriptionWord> {
+        self.utterances.iter().flat_map(|u| u.result.iter())
+    }
+
+    pub fn write<W: Write>(&self, format: OutputFormat, out: &mut W) -> io::Result<()> {
+        match format {
+            OutputFormat::Text => writeln!(out, "{}", self.text),
+            OutputFormat::Json => {
+                serde_json::to_writer(&mut *out, self)?;
+                writeln!(out)
+            }
+            OutputFormat::Srt => {
+                for (idx, cue) in self.cues().iter().enumerate() {
+                    writeln!(out, "{}", idx + 1)?;
+                    writeln!(out, "{} --> {}", timestamp(cue.start, ','), timestamp(cue.end, ','))?;
+                    writeln!(out, "{}\n", cue.text)?;
+                }
+                Ok(())
+            }
+            OutputFormat::Vtt => {
+                writeln!(out, "WEBVTT\n")?;
+                for cue in self.cues() {
+                    writeln!(out, "{} --> {}", timestamp(cue.start, '.'), timestamp(cue.end, '.'))?;
+                    writeln!(out, "{}\n", cue.text)?;
+                }
+                Ok(())
+            }
+            OutputFormat::Ctm => {
+                for word in self.words() {
+                    writeln!(
+                        out,
+                        "{} 1 {:.3} {:.3} {} {:.2}",
+                        self.id,
+                        word.start,
+                        word.end - word.start,
+                        word.word,
+                        word.conf
+                    )?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Subtitle cues, one per utterance, split so that a cue stays readable.
+    fn cues(&self) -> Vec<Cue> {
+        let mut cues = Vec::new();
+
+        for utterance in &self.utterances {
+            let mut words: Vec<&TranscriptionWord> = Vec::new();
+
+            for word in &utterance.result {
+                let too_long = words.len() >= MAX_CUE_WORDS
+                    || words.first().is_some_and(|first| word.end - first.start > MAX_CUE_DURATION);
+
+                if too_long {
+                    cues.push(Cue::new(&words));
+                    words.clear();
+                }
+
+                words.push(word);
+            }
+
+            if !words.is_empty() {
+                cues.push(Cue::new(&words));
+            }
+        }
+
+        cues
+    }
+}
+
+struct Cue {
+    start: f32,
+    end: f32,
+    text: String,
+}
+
+impl Cue {
+    fn new(words: &[&TranscriptionWord]) -> Self {
+        Self {
+            start: words[0].start,
+            end: words[words.len() - 1].end,
+            text: words.iter().map(|w| w.word.as_str()).collect::<Vec<_>>().join(" "),
+        }
+    }
+}
+
+fn timestamp(seconds: f32, separator: char) -> String {
+    let millis = (seconds.max(0.0) * 1000.0).round() as u64;
+
+    format!(
+        "{:02}:{:02}:{:02}{}{:03}",
+        millis / 3_600_000,
+        millis / 60_000 % 60,
+        millis / 1000 % 60,
+        separator,
+        millis % 1000
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utterance(words: &[(&str, f32, f32)]) -> TranscriptionResult {
+        TranscriptionResult {
+            text: words.iter().map(|w| w.0).collect::<Vec<_>>().join(" "),
+            result: words
+                .iter()
+                .map(|&(word, start, end)| TranscriptionWord {
+                    conf: 0.875,
+                    start,
+                    end,
+                    word: word.to_string(),
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    fn write(transcript: &Transcript, format: OutputFormat) -> String {
+        let mut out = Vec::new();
+        transcript.write(format, &mut out).unwrap();
+
+        String::from_utf8(out).unwrap()
+    }
+
+    fn transcript() -> Transcript {
+        Transcript::new(
+            "talk",
+            vec![
+                utterance(&[("hello", 0.5, 0.9), ("world", 1.0, 1.25)]),
+                TranscriptionResult::default(),
+                utterance(&[("bye", 3661.0, 3661.4567)]),
+            ],
+        )
+    }
+
+    #[test]
+    fn timestamps() {
+        assert_eq!(timestamp(0.0, ','), "00:00:00,000");
+        assert_eq!(timestamp(1.2345, ','), "00:00:01,235");
+        assert_eq!(timestamp(59.9996, '.'), "00:01:00.000");
+        assert_eq!(timestamp(3599.5, '.'), "00:59:59.500");
+        assert_eq!(timestamp(3661.25, ','), "01:01:01,250");
+        assert_eq!(timestamp(36000.0, ','), "10:00:00,000");
+        assert_eq!(timestamp(-1.0, ','), "00:00:00,000");
+    }
+
+    #[test]
+    fn text_skips_empty_utterances() {
+        assert_eq!(write(&transcript(), OutputFormat::Text), "hello world bye\n");
+    }
+
+    #[test]
+    fn srt() {
+        assert_eq!(
+            write(&transcript(), OutputFormat::Srt),
+            "1\n00:00:00,500 --> 00:00:01,250\nhello world\n\n2\n01:01:01,000 --> 01:01:01,457\nbye\n\n"
+        );
+    }
+
+    #[test]
+    fn vtt() {
+        assert_eq!(
+            write(&transcript(), OutputFormat::Vtt),
+            "WEBVTT\n\n00:00:00.500 --> 00:00:01.250\nhello world\n\n01:01:01.000 --> 01:01:01.457\nbye\n\n"
+        );
+    }
+
+    #[test]
+    fn ctm() {
+        assert_eq!(
+            write(&transcript(), OutputFormat::Ctm),
+            "talk 1 0.500 0.400 hello 0.88\ntalk 1 1.000 0.250 world 0.88\ntalk 1 3661.000 0.457 bye 0.88\n"
+        );
+    }
+
+    #[test]
+    fn cues_split_long_utterances() {
+        // Twelve half-second words: the first cue ends at ten words.
+        let words: Vec<(String, f32, f32)> = (0..12)
+            .map(|idx| (format!("w{}", idx), idx as f32 * 0.5, idx as f32 * 0.5 + 0.4))
+            .collect();
+        let words: Vec<(&str, f32, f32)> = words.iter().map(|(w, s, e)| (w.as_str(), *s, *e)).collect();
+        let cues = Transcript::new("a", vec![utterance(&words)]).cues();
+
+        let cues: Vec<_> = cues.iter().map(|c| (c.start, c.end, c.text.as_str())).collect();
+        assert_eq!(
+            cues,
+            vec![
+                (0.0, 4.9, "w0 w1 w2 w3 w4 w5 w6 w7 w8 w9"),
+                (5.0, 5.9, "w10 w11"),
+            ]
+        );
+
+        // Slow speech: a cue ends before it would last over five seconds.
+        let cues = Transcript::new(
+            "a",
+            vec![utterance(&[("one", 0.0, 1.0), ("two", 2.0, 3.0), ("three", 4.0, 5.0), ("four", 5.5, 6.0)])],
+        )
+        .cues();
+
+        let cues: Vec<_> = cues.iter().map(|c| (c.start, c.end, c.text.as_str())).collect();
+        assert_eq!(cues, vec![(0.0, 5.0, "one two three"), (5.5, 6.0, "four")]);
+    }
+
+    #[test]
+    fn parse_formats() {
+        assert_eq!("SRT".parse::<OutputFormat>(), Ok(OutputFormat::Srt));
+        assert_eq!("txt".parse::<OutputFormat>(), Ok(OutputFormat::Text));
+        assert!("doc".parse::<OutputFormat>().is_err());
+        assert_eq!(OutputFormat::Text.extension(), "txt");
+        assert_eq!(OutputFormat::Vtt.to_string(), "vtt");
+    }
+}
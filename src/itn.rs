@@ -0,0 +1,703 @@
+use crate::transcription::{TranscriptionPartialResult, TranscriptionResult, TranscriptionWord};
+
+const MONTHS: [&str; 12] = [
+    "january", "february", "march", "april", "may", "june", "july", "august", "september", "october", "november",
+    "december",
+];
+
+// Words after which "eleven twenty" or "two oh five" is a time without a
+// meridiem, and "nineteen ninety" a year.
+const TIME_CONTEXT: [&str; 7] = ["at", "by", "until", "till", "around", "before", "after"];
+const YEAR_CONTEXT: [&str; 5] = ["in", "since", "year", "circa", "during"];
+
+/// Rule-based English inverse text normalization: rewrites spoken numbers,
+/// ordinals, decimals, percentages, currency, times, dates and phone numbers
+/// into written form. Each rewritten token spans the words it replaced, so
+/// timings are kept.
+#[derive(Debug, Clone, Default)]
+pub struct InverseNormalizer {
+    single_digits: bool,
+}
+
+impl InverseNormalizer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether lone numbers below ten ("one", "first") are rewritten too; off
+    /// by default since they are usually better left as words.
+    #[inline]
+    pub fn set_single_digits(&mut self, single_digits: bool) {
+        self.single_digits = single_digits;
+    }
+
+    pub fn normalize(&self, result: &TranscriptionResult) -> TranscriptionResult {
+        result.with_tokens(self.normalize_words(&result.tokens()))
+    }
+
+    pub fn normalize_partial(&self, partial: &TranscriptionPartialResult) -> TranscriptionPartialResult {
+        TranscriptionPartialResult {
+            partial: self.normalize_text(&partial.partial),
+        }
+    }
+
+    pub fn normalize_text(&self, text: &str) -> String {
+        let result = TranscriptionResult {
+            text: text.to_string(),
+            ..Default::default()
+        };
+
+        self.normalize(&result).text
+    }
+
+    pub fn normalize_words(&self, words: &[TranscriptionWord]) -> Vec<TranscriptionWord> {
+        let lower: Vec<String> = words.iter().map(|w| w.word.to_lowercase()).collect();
+        let lower: Vec<&str> = lower.iter().map(String::as_str).collect();
+
+        let mut out = Vec::with_capacity(words.len());
+        let mut idx = 0;
+
+        while idx < words.len() {
+            let prev = idx.checked_sub(1).map(|prev| lower[prev]);
+
+            match self.rewrite(prev, &lower[idx..]) {
+                Some((len, token)) => {
+                    out.push(TranscriptionWord::merge(&words[idx..idx + len], token));
+                    idx += len;
+                }
+                None => {
+                    out.push(words[idx].clone());
+                    idx += 1;
+                }
+            }
+        }
+
+        out
+    }
+
+    /// The number of leading words replaced and their written form, given
+    /// the word before them.
+    fn rewrite(&self, prev: Option<&str>, words: &[&str]) -> Option<(usize, String)> {
+        let in_context = |context: &[&str]| prev.is_some_and(|prev| context.contains(&prev));
+
+        phone(words)
+            .or_else(|| time(words, in_context(&TIME_CONTEXT)))
+            .or_else(|| date(words))
+            .or_else(|| {
+                // Bare, "fifteen twenty" is as likely a count or a time.
+                spoken_year(words)
+                    .filter(|_| in_context(&YEAR_CONTEXT))
+                    .map(|(len, year)| (len, year.to_string()))
+            })
+            .or_else(|| self.number(prev, words))
+    }
+
+    fn number(&self, prev: Option<&str>, words: &[&str]) -> Option<(usize, String)> {
+        let (sign, start) = match words[0] {
+            "minus" | "negative" => ("-", 1),
+            _ => ("", 0),
+        };
+
+        let number = match cardinal(&words[start..]) {
+            Some(number) => number,
+            // "point five"
+            None if words.get(start) == Some(&"point") => Number {
+                value: 0,
+                len: 0,
+                ordinal: false,
+            },
+            None => return None,
+        };
+
+        let mut end = start + number.len;
+        let simple = number.len == 1 && number.value < 10;
+
+        if number.ordinal {
+            if (simple && !self.single_digits) || !sign.is_empty() {
+                return None;
+            }
+
+            return Some((end, format!("{}{}", number.value, ordinal_suffix(number.value))));
+        }
+
+        let mut text = format!("{}{}", sign, group(number.value));
+        let mut decimal = false;
+
+        if words.get(end) == Some(&"point") {
+            let digits: String = words[end + 1..].iter().map_while(|w| digit(w)).map(|d| d.to_string()).collect();
+            if !digits.is_empty() {
+                end += 1 + digits.len();
+                text = format!("{}{}.{}", sign, number.value, digits);
+                decimal = true;
+            }
+        }
+
+        if number.len == 0 && !decimal {
+            return None;
+        }
+
+        if let Some((symbol, subunits)) = words.get(end).and_then(|w| currency(w)) {
+            end += 1;
+
+            let cents = match (words.get(end), decimal) {
+                (Some(&"and"), false) => cardinal(&words[end + 1..])
+                    .filter(|c| !c.ordinal && c.value < 100)
+                    .filter(|c| words.get(end + 1 + c.len).is_some_and(|w| subunits.contains(w))),
+                _ => None,
+            };
+
+            let amount = match cents {
+                Some(cents) => {
+                    end += 1 + cents.len + 1;
+                    format!("{}.{:02}", text.trim_start_matches('-'), cents.value)
+                }
+                None => text.trim_start_matches('-').to_string(),
+            };
+
+            return Some((end, format!("{}{}{}", sign, symbol, amount)));
+        }
+
+        if !decimal && number.value < 100 && words.get(end).is_some_and(|w| *w == "cent" || *w == "cents") {
+            return Some((end + 1, format!("{}$0.{:02}", sign, number.value)));
+        }
+
+        if words.get(end) == Some(&"percent") {
+            return Some((end + 1, format!("{}%", text)));
+        }
+
+        if simple && !decimal && !self.single_digits {
+            return None;
+        }
+
+        // Runs of separate numbers ("twelve fifteen", "one two three") are
+        // left alone rather than written as "12 15".
+        if !decimal && (prev.is_some_and(is_number_word) || words.get(end).is_some_and(|w| is_number_word(w))) {
+            return None;
+        }
+
+        Some((end, text))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Kind {
+    Unit,
+    Teen,
+    Tens,
+    Hundred,
+    Scale,
+    And,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Number {
+    value: u64,
+    len: usize,
+    ordinal: bool,
+}
+
+/// Parses the longest spoken cardinal or ordinal at the start of `words`.
+fn cardinal(words: &[&str]) -> Option<Number> {
+    match words.first() {
+        Some(&"zero") => {
+            return Some(Number {
+                value: 0,
+                len: 1,
+                ordinal: false,
+            })
+        }
+        None => return None,
+        _ => {}
+    }
+
+    let mut total = 0;
+    let mut current = 0;
+    let mut last: Option<Kind> = None;
+    let mut last_scale = u64::MAX;
+    let mut len = 0;
+    let mut ordinal = false;
+    let mut idx = 0;
+
+    // "a hundred", "a thousand"
+    if words[0] == "a" && matches!(words.get(1).and_then(|w| classify(w)), Some((Kind::Hundred | Kind::Scale, _, _))) {
+        current = 1;
+        last = Some(Kind::Unit);
+        idx = 1;
+    }
+
+    while let Some(word) = words.get(idx) {
+        if *word == "and" {
+            let next_is_small = words
+                .get(idx + 1)
+                .and_then(|w| classify(w))
+                .is_some_and(|(kind, _, _)| matches!(kind, Kind::Unit | Kind::Teen | Kind::Tens));
+
+            if matches!(last, Some(Kind::Hundred | Kind::Scale)) && next_is_small {
+                last = Some(Kind::And);
+                idx += 1;
+                continue;
+            }
+            break;
+        }
+
+        let (kind, value, is_ordinal) = match classify(word) {
+            Some(token) => token,
+            None => break,
+        };
+
+        let after_small = matches!(last, Some(Kind::Unit | Kind::Teen | Kind::Tens));
+        let valid = match kind {
+            Kind::Unit => matches!(last, None | Some(Kind::Tens | Kind::Hundred | Kind::Scale | Kind::And)),
+            Kind::Teen | Kind::Tens => matches!(last, None | Some(Kind::Hundred | Kind::Scale | Kind::And)),
+            Kind::Hundred => after_small && (1..100).contains(&current),
+            Kind::Scale => (after_small || last == Some(Kind::Hundred)) && value < last_scale,
+            Kind::And => false,
+        };
+
+        if !valid {
+            break;
+        }
+
+        match kind {
+            Kind::Hundred => current *= 100,
+            Kind::Scale => {
+                total += current * value;
+                current = 0;
+                last_scale = value;
+            }
+            _ => current += value,
+        }
+
+        last = Some(kind);
+        idx += 1;
+        len = idx;
+
+        if is_ordinal {
+            ordinal = true;
+            break;
+        }
+    }
+
+    if len == 0 {
+        return None;
+    }
+
+    Some(Number {
+        value: total + current,
+        len,
+        ordinal,
+    })
+}
+
+/// The kind and value of a number word, and whether it was an ordinal.
+fn classify(word: &str) -> Option<(Kind, u64, bool)> {
+    let (base, ordinal) = match ordinal_base(word) {
+        Some(base) => (base, true),
+        None => (word, false),
+    };
+
+    let (kind, value) = match base {
+        "one" => (Kind::Unit, 1),
+        "two" => (Kind::Unit, 2),
+        "three" => (Kind::Unit, 3),
+        "four" => (Kind::Unit, 4),
+        "five" => (Kind::Unit, 5),
+        "six" => (Kind::Unit, 6),
+        "seven" => (Kind::Unit, 7),
+        "eight" => (Kind::Unit, 8),
+        "nine" => (Kind::Unit, 9),
+        "ten" => (Kind::Teen, 10),
+        "eleven" => (Kind::Teen, 11),
+        "twelve" => (Kind::Teen, 12),
+        "thirteen" => (Kind::Teen, 13),
+        "fourteen" => (Kind::Teen, 14),
+        "fifteen" => (Kind::Teen, 15),
+        "sixteen" => (Kind::Teen, 16),
+        "seventeen" => (Kind::Teen, 17),
+        "eighteen" => (Kind::Teen, 18),
+        "nineteen" => (Kind::Teen, 19),
+        "twenty" => (Kind::Tens, 20),
+        "thirty" => (Kind::Tens, 30),
+        "forty" => (Kind::Tens, 40),
+        "fifty" => (Kind::Tens, 50),
+        "sixty" => (Kind::Tens, 60),
+        "seventy" => (Kind::Tens, 70),
+        "eighty" => (Kind::Tens, 80),
+        "ninety" => (Kind::Tens, 90),
+        "hundred" => (Kind::Hundred, 100),
+        "thousand" => (Kind::Scale, 1_000),
+        "million" => (Kind::Scale, 1_000_000),
+        "billion" => (Kind::Scale, 1_000_000_000),
+        "trillion" => (Kind::Scale, 1_000_000_000_000),
+        _ => return None,
+    };
+
+    Some((kind, value, ordinal))
+}
+
+fn is_number_word(word: &str) -> bool {
+    matches!(classify(word), Some((Kind::Unit | Kind::Teen | Kind::Tens, _, false)))
+}
+
+fn ordinal_base(word: &str) -> Option<&str> {
+    let base = match word {
+        "first" => "one",
+        "second" => "two",
+        "third" => "three",
+        "fifth" => "five",
+        "eighth" => "eight",
+        "ninth" => "nine",
+        "twelfth" => "twelve",
+        _ => match word.strip_suffix("ieth") {
+            Some(stem) => return tens_from_stem(stem),
+            None => word.strip_suffix("th")?,
+        },
+    };
+
+    Some(base)
+}
+
+fn tens_from_stem(stem: &str) -> Option<&'static str> {
+    match stem {
+        "twent" => Some("twenty"),
+        "thirt" => Some("thirty"),
+        "fort" => Some("forty"),
+        "fift" => Some("fifty"),
+        "sixt" => Some("sixty"),
+        "sevent" => Some("seventy"),
+        "eight" => Some("eighty"),
+        "ninet" => Some("ninety"),
+        _ => None,
+    }
+}
+
+fn ordinal_suffix(value: u64) -> &'static str {
+    match (value % 10, value % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    }
+}
+
+/// Digits with thousands separators from five digits up, so years and
+/// four-digit amounts stay ungrouped.
+fn group(value: u64) -> String {
+    let digits = value.to_string();
+    if digits.len() < 5 {
+        return digits;
+    }
+
+    let groups: Vec<&str> = digits
+        .as_bytes()
+        .rchunks(3)
+        .rev()
+        .map(|g| std::str::from_utf8(g).unwrap())
+        .collect();
+
+    groups.join(",")
+}
+
+fn digit(word: &str) -> Option<u8> {
+    match word {
+        "zero" | "oh" | "o" => Some(0),
+        "one" => Some(1),
+        "two" => Some(2),
+        "three" => Some(3),
+        "four" => Some(4),
+        "five" => Some(5),
+        "six" => Some(6),
+        "seven" => Some(7),
+        "eight" => Some(8),
+        "nine" => Some(9),
+        _ => None,
+    }
+}
+
+/// The symbol of a currency word and the words for its hundredths.
+fn currency(word: &str) -> Option<(&'static str, &'static [&'static str])> {
+    match word {
+        "dollar" | "dollars" => Some(("$", &["cent", "cents"])),
+        "euro" | "euros" => Some(("€", &["cent", "cents"])),
+        "pound" | "pounds" => Some(("£", &["penny", "pence"])),
+        "yen" => Some(("¥", &[])),
+        _ => None,
+    }
+}
+
+/// Seven or more spoken digits: "555-0123", "555-123-4567",
+/// "1-555-123-4567", otherwise the digits run together. "hundred" after a
+/// digit reads as two zeros, as in "one eight hundred".
+fn phone(words: &[&str]) -> Option<(usize, String)> {
+    let mut digits = String::new();
+    let mut len = 0;
+
+    for word in words {
+        match digit(word) {
+            Some(d) => digits.push((b'0' + d) as char),
+            None if *word == "hundred" && len > 0 => digits.push_str("00"),
+            None => break,
+        }
+        len += 1;
+    }
+
+    if digits.len() < 7 {
+        return None;
+    }
+
+    let formatted = match digits.len() {
+        7 => format!("{}-{}", &digits[..3], &digits[3..]),
+        10 => format!("{}-{}-{}", &digits[..3], &digits[3..6], &digits[6..]),
+        11 if digits.starts_with('1') => format!("1-{}-{}-{}", &digits[1..4], &digits[4..7], &digits[7..]),
+        _ => digits.clone(),
+    };
+
+    Some((len, formatted))
+}
+
+/// "three o'clock", "ten thirty p m", "nine am"; "seven oh five" and
+/// "eleven twenty" only with a meridiem or after a word like "at".
+fn time(words: &[&str], in_context: bool) -> Option<(usize, String)> {
+    let hour = cardinal(words).filter(|n| !n.ordinal && n.len == 1 && (1..=12).contains(&n.value))?;
+    let rest = &words[1..];
+
+    let (minutes, len, needs_meridiem) = match rest {
+        ["o'clock", ..] => (0, 1, false),
+        ["o", "clock", ..] => (0, 2, false),
+        ["oh" | "o", m, ..] => match digit(m) {
+            Some(d) if d > 0 => (d as u64, 2, !in_context),
+            _ => return None,
+        },
+        _ => match cardinal(rest).filter(|n| !n.ordinal && (10..60).contains(&n.value) && n.len <= 2) {
+            Some(minutes) => (minutes.value, minutes.len, !in_context),
+            None => (0, 0, true),
+        },
+    };
+
+    let meridiem = meridiem(&rest[len..]);
+    if needs_meridiem && meridiem.is_none() {
+        return None;
+    }
+
+    let mut text = match (len, meridiem) {
+        (0, _) => hour.value.to_string(),
+        _ => format!("{}:{:02}", hour.value, minutes),
+    };
+
+    let mut end = 1 + len;
+    if let Some((meridiem_len, meridiem)) = meridiem {
+        text.push(' ');
+        text.push_str(meridiem);
+        end += meridiem_len;
+    }
+
+    Some((end, text))
+}
+
+fn meridiem(words: &[&str]) -> Option<(usize, &'static str)> {
+    match words {
+        ["a", "m", ..] => Some((2, "AM")),
+        ["p", "m", ..] => Some((2, "PM")),
+        ["am" | "a.m." | "a.m", ..] => Some((1, "AM")),
+        ["pm" | "p.m." | "p.m", ..] => Some((1, "PM")),
+        _ => None,
+    }
+}
+
+/// "january fifth", "march third twenty twenty one", "the tenth of may".
+fn date(words: &[&str]) -> Option<(usize, String)> {
+    if MONTHS.contains(&words[0]) {
+        let day = cardinal(&words[1..]).filter(|n| (1..=31).contains(&n.value))?;
+        let end = 1 + day.len;
+
+        return match year(&words[end..]) {
+            Some((len, year)) => Some((end + len, format!("{} {}, {}", words[0], day.value, year))),
+            // "may" and "march" double as common words, so a cardinal day
+            // ("may five") is only taken with a year.
+            None if day.ordinal => Some((end, format!("{} {}", words[0], day.value))),
+            None => None,
+        };
+    }
+
+    let day = cardinal(words).filter(|n| n.ordinal && (1..=31).contains(&n.value))?;
+    if words.get(day.len) != Some(&"of") {
+        return None;
+    }
+
+    let month = words.get(day.len + 1).filter(|w| MONTHS.contains(w))?;
+    let end = day.len + 2;
+
+    match year(&words[end..]) {
+        Some((len, year)) => Some((end + len, format!("{} {}, {}", month, day.value, year))),
+        None => Some((end, format!("{} {}", month, day.value))),
+    }
+}
+
+/// "nineteen ninety", "nineteen oh five", "twenty twenty one",
+/// "nineteen hundred", "two thousand and five".
+fn year(words: &[&str]) -> Option<(usize, u64)> {
+    if let Some(year) = spoken_year(words) {
+        return Some(year);
+    }
+
+    cardinal(words)
+        .filter(|n| !n.ordinal && (1000..2100).contains(&n.value))
+        .map(|n| (n.len, n.value))
+}
+
+/// A year spoken as two pairs of digits, from 1100 to 2099; these rarely
+/// mean anything else.
+fn spoken_year(words: &[&str]) -> Option<(usize, u64)> {
+    let (len, century) = two_digits(words).filter(|(_, v)| (11..=20).contains(v))?;
+    let rest = &words[len..];
+
+    let (tail_len, tail) = match rest {
+        ["hundred", ..] => (1, 0),
+        ["oh" | "o", d, ..] => digit(d).filter(|d| *d > 0).map(|d| (2, d as u64))?,
+        _ => two_digits(rest).filter(|(_, v)| *v >= 20)?,
+    };
+
+    Some((len + tail_len, century * 100 + tail))
+}
+
+/// A number from ten to ninety-nine spoken as one or two words.
+fn two_digits(words: &[&str]) -> Option<(usize, u64)> {
+    match classify(words.first()?)? {
+        (Kind::Teen, value, false) => Some((1, value)),
+        (Kind::Tens, value, false) => match words.get(1).and_then(|w| classify(w)) {
+            Some((Kind::Unit, unit, false)) => Some((2, value + unit)),
+            _ => Some((1, value)),
+        },
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(cases: &[(&str, &str)]) {
+        let normalizer = InverseNormalizer::new();
+        for (spoken, written) in cases {
+            assert_eq!(normalizer.normalize_text(spoken), *written, "{:?}", spoken);
+        }
+    }
+
+    #[test]
+    fn cardinals() {
+        check(&[
+            ("twenty three", "23"),
+            ("one hundred and five", "105"),
+            ("a thousand people", "1000 people"),
+            ("two million three hundred thousand", "2,300,000"),
+            ("minus forty", "-40"),
+            ("zero", "zero"),
+            ("one question", "one question"),
+            ("twelve fifteen", "twelve fifteen"),
+        ]);
+    }
+
+    #[test]
+    fn single_digits() {
+        let mut normalizer = InverseNormalizer::new();
+        normalizer.set_single_digits(true);
+        assert_eq!(normalizer.normalize_text("one question"), "1 question");
+        assert_eq!(normalizer.normalize_text("the first one"), "the 1st 1");
+    }
+
+    #[test]
+    fn ordinals() {
+        check(&[
+            ("the twenty first", "the 21st"),
+            ("twelfth", "12th"),
+            ("one hundred and third", "103rd"),
+            ("the fortieth", "the 40th"),
+            ("the first", "the first"),
+        ]);
+    }
+
+    #[test]
+    fn decimals_and_percentages() {
+        check(&[
+            ("three point one four", "3.14"),
+            ("point five", "0.5"),
+            ("fifty percent", "50%"),
+            ("twelve point five percent", "12.5%"),
+        ]);
+    }
+
+    #[test]
+    fn currency() {
+        check(&[
+            ("twenty three dollars and fifty cents", "$23.50"),
+            ("five pounds", "£5"),
+            ("ninety nine cents", "$0.99"),
+            ("minus ten euros", "-€10"),
+            ("fifteen twenty dollar bills", "fifteen $20 bills"),
+        ]);
+    }
+
+    #[test]
+    fn times() {
+        check(&[
+            ("three o'clock", "3:00"),
+            ("ten thirty p m", "10:30 PM"),
+            ("nine am", "9 AM"),
+            ("seven oh five a m", "7:05 AM"),
+            ("see you at eleven twenty", "see you at 11:20"),
+            ("meet me at two oh five", "meet me at 2:05"),
+            ("room two oh five", "room two oh five"),
+            ("eleven twenty people", "eleven twenty people"),
+        ]);
+    }
+
+    #[test]
+    fn dates_and_years() {
+        check(&[
+            ("january fifth", "january 5"),
+            ("march third twenty twenty one", "march 3, 2021"),
+            ("tenth of may", "may 10"),
+            ("may five", "may five"),
+            ("born in nineteen eighty four", "born in 1984"),
+            ("since nineteen oh five", "since 1905"),
+            ("in two thousand and five", "in 2005"),
+            ("fifteen twenty", "fifteen twenty"),
+        ]);
+    }
+
+    #[test]
+    fn phones() {
+        check(&[
+            ("five five five oh one two three", "555-0123"),
+            ("five five five one two three four five six seven", "555-123-4567"),
+            ("one eight hundred five five five one two three four", "1-800-555-1234"),
+        ]);
+    }
+
+    #[test]
+    fn rewritten_words_keep_timings() {
+        let word = |word: &str, start: f32, end: f32, conf: f32| TranscriptionWord {
+            word: word.to_string(),
+            start,
+            end,
+            conf,
+        };
+
+        let words = [
+            word("about", 0.0, 0.3, 1.0),
+            word("twenty", 0.4, 0.7, 0.8),
+            word("five", 0.7, 1.0, 0.6),
+            word("people", 1.1, 1.5, 1.0),
+        ];
+
+        let normalized = InverseNormalizer::new().normalize_words(&words);
+
+        assert_eq!(normalized.len(), 3);
+        assert_eq!(normalized[0].word, "about");
+        assert_eq!(normalized[1].word, "25");
+        assert_eq!((normalized[1].start, normalized[1].end), (0.4, 1.0));
+        assert!((normalized[1].conf - 0.7).abs() < 1e-6);
+        assert_eq!((normalized[2].word.as_str(), normalized[2].start), ("people", 1.1));
+    }
+}
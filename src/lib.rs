@@ -1,25 +1,89 @@
+#[cfg(feature = "bundled")]
 extern crate cblas_src;
+#[cfg(feature = "bundled")]
 extern crate openblas_src;
 
+// OpenFST verbosity, updated by `set_log_level`. `AtomicI32` has the layout
+// of the `int32` OpenFST declares.
+#[cfg(feature = "bundled")]
 #[used]
 #[no_mangle]
-pub static FLAGS_v: i32 = 0;
+pub static FLAGS_v: std::sync::atomic::AtomicI32 = std::sync::atomic::AtomicI32::new(0);
 
+#[cfg(not(feature = "dynamic"))]
 mod ffi {
     #![allow(non_upper_case_globals)]
     #![allow(non_camel_case_types)]
     #![allow(non_snake_case)]
     #![allow(dead_code)]
     
+    #[cfg(feature = "bindgen")]
     include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
+    #[cfg(not(feature = "bindgen"))]
+    include!("bindings.rs");
 }
 
+// With `dynamic` libvosk is loaded at runtime and the bindings are replaced by
+// functions calling through the loaded symbols.
+#[cfg(feature = "dynamic")]
+mod dynamic;
+#[cfg(feature = "dynamic")]
+use dynamic as ffi;
+
+mod alignment;
+#[cfg(feature = "archive")]
+mod archive;
+#[cfg(feature = "wav")]
+mod audio;
+mod command;
+#[cfg(feature = "wav")]
+mod corpus;
+mod diarization;
+mod error;
+mod format;
+mod itn;
+mod logging;
 mod model;
+mod model_info;
+mod profanity;
+mod punctuation;
+mod recognizer;
+mod registry;
 mod session;
 mod speaker;
+mod stats;
 mod transcription;
+mod vocabulary;
+mod voiceprint;
+mod wer;
 
+pub use alignment::{AlignedWord, ForcedAligner, ForcedAlignment};
+#[cfg(feature = "archive")]
+pub use archive::ModelArchive;
+#[cfg(feature = "wav")]
+pub use audio::{read_wav, read_wav_from, Audio};
+pub use command::{CommandMatch, CommandRecognizer, CommandRecognizerBuilder};
+#[cfg(feature = "wav")]
+pub use corpus::{Corpus, CorpusEntry, CorpusReport, FileFailure, FileReport};
+#[cfg(feature = "dynamic")]
+pub use dynamic::{is_library_loaded, load_library};
+pub use diarization::{Clustering, DiarizedSegment, Diarizer};
+pub use error::VoskError;
+pub use format::{OutputFormat, Transcript};
+pub use itn::InverseNormalizer;
+#[cfg(all(feature = "log-bridge", unix))]
+pub use logging::install_log_bridge;
+pub use logging::set_log_level;
 pub use model::VoskModel;
+pub use model_info::{GraphKind, ModelInfo, ModelLayout, Severity, ValidationIssue, ValidationReport};
+pub use profanity::{FilterAction, ProfanityFilter};
+pub use punctuation::Punctuator;
+pub use recognizer::{FakeRecognizer, FakeRecognizerBuilder, Recognizer};
+pub use registry::{ModelEntry, ModelRegistry};
 pub use session::{VoskSession, VoskSessionConfig, VoskSessionConfigBuilder};
-pub use speaker::SpeakerModel;
-pub use transcription::{TranscriptionResult, TranscriptionPartialResult, TranscriptionWord};
\ No newline at end of file
+pub use speaker::{cosine_similarity, SpeakerModel};
+pub use stats::SessionStats;
+pub use transcription::{TranscriptionResult, TranscriptionPartialResult, TranscriptionWord};
+pub use vocabulary::Vocabulary;
+pub use voiceprint::{SpeakerMatch, Verification, Voiceprint, VoiceprintStore};
+pub use wer::{normalize, AlignedPair, Alignment, CorpusEvaluation, EditOp, ErrorCounts, Evaluation};
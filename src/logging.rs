@@ -0,0 +1,162 @@
+use crate::ffi;
+
+/// Sets the verbosity of Kaldi and Vosk messages: negative values only keep
+/// warnings and errors, `0` (the default) adds info messages and positive
+/// values enable increasingly verbose debug output.
+///
+/// With the `dynamic` feature a level set before libvosk can be loaded is
+/// applied when it is.
+pub fn set_log_level(level: i32) {
+    #[cfg(feature = "bundled")]
+    crate::FLAGS_v.store(level, std::sync::atomic::Ordering::Relaxed);
+
+    unsafe { ffi::vosk_set_log_level(level) }
+}
+
+#[cfg(all(feature = "log-bridge", unix))]
+pub use self::bridge::install_log_bridge;
+
+#[cfg(all(feature = "log-bridge", unix))]
+mod bridge {
+    use std::fs::File;
+    use std::io::{self, BufRead, BufReader, Write};
+    use std::os::unix::io::FromRawFd;
+    use std::sync::OnceLock;
+
+    use log::Level;
+
+    use crate::error::VoskError;
+
+    const TARGET: &str = "vosk::kaldi";
+
+    // The outcome of the one installation attempt, repeated to every caller.
+    static INSTALL: OnceLock<Result<(), (io::ErrorKind, String)>> = OnceLock::new();
+
+    /// Captures everything written to stderr and forwards Kaldi/Vosk log lines
+    /// to the `log` crate; other output is passed through to the original
+    /// stderr. Kaldi's verbosity is matched to `log::max_level()`.
+    ///
+    /// `tracing` subscribers receive the messages through `tracing-log`.
+    ///
+    /// The `log` backend must not write to stderr: stderr is the pipe read by
+    /// the bridge, so its output would be read back in. Log to a file or
+    /// stdout instead.
+    pub fn install_log_bridge() -> Result<(), VoskError> {
+        INSTALL
+            .get_or_init(|| install().map_err(|err| (err.kind(), err.to_string())))
+            .clone()
+            .map_err(|(kind, message)| VoskError::Io(io::Error::new(kind, message)))?;
+
+        super::set_log_level(match log::max_level() {
+            log::LevelFilter::Off | log::LevelFilter::Error | log::LevelFilter::Warn => -1,
+            log::LevelFilter::Info => 0,
+            log::LevelFilter::Debug => 1,
+            log::LevelFilter::Trace => 2,
+        });
+
+        Ok(())
+    }
+
+    fn install() -> io::Result<()> {
+        let mut fds = [0; 2];
+
+        unsafe {
+            if libc::pipe(fds.as_mut_ptr()) != 0 {
+                return Err(io::Error::last_os_error());
+            }
+
+            let stderr = libc::dup(libc::STDERR_FILENO);
+            if stderr < 0 {
+                return Err(io::Error::last_os_error());
+            }
+
+            if libc::dup2(fds[1], libc::STDERR_FILENO) < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            libc::close(fds[1]);
+
+            let reader = BufReader::new(File::from_raw_fd(fds[0]));
+            let stderr = File::from_raw_fd(stderr);
+
+            std::thread::Builder::new()
+                .name("vosk-log-bridge".to_string())
+                .spawn(move || forward(reader, stderr))?;
+        }
+
+        Ok(())
+    }
+
+    fn forward(mut reader: BufReader<File>, mut stderr: File) {
+        let mut buf = Vec::new();
+
+        loop {
+            buf.clear();
+            match reader.read_until(b'\n', &mut buf) {
+                Ok(0) | Err(_) => break,
+                Ok(_) => {}
+            }
+
+            let line = String::from_utf8_lossy(&buf);
+            match parse_line(line.trim_end()) {
+                Some((level, message)) => log::log!(target: TARGET, level, "{}", message),
+                None => {
+                    let _ = stderr.write_all(&buf);
+                }
+            }
+        }
+    }
+
+    /// Parses `LOG (VoskAPI:Model():model.cc:122) message` style lines.
+    fn parse_line(line: &str) -> Option<(Level, &str)> {
+        let (prefix, rest) = line.split_at(line.find(" (")?);
+
+        let level = match prefix {
+            "LOG" => Level::Info,
+            "WARNING" => Level::Warn,
+            "ERROR" | "ASSERTION_FAILED" => Level::Error,
+            "VLOG[1]" => Level::Debug,
+            _ if prefix.starts_with("VLOG[") => Level::Trace,
+            _ => return None,
+        };
+
+        let message = match rest.find(") ") {
+            Some(idx) => &rest[idx + 2..],
+            None => rest.trim_start(),
+        };
+
+        Some((level, message))
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn parse_levels() {
+            assert_eq!(
+                parse_line("LOG (VoskAPI:Model():model.cc:122) Decoding params beam=13"),
+                Some((Level::Info, "Decoding params beam=13"))
+            );
+            assert_eq!(
+                parse_line("WARNING (VoskAPI:Model():model.cc:140) Missing conf"),
+                Some((Level::Warn, "Missing conf"))
+            );
+            assert_eq!(parse_line("ERROR (VoskAPI:Open():io.cc:7) No such file"), Some((Level::Error, "No such file")));
+            assert_eq!(parse_line("ASSERTION_FAILED (VoskAPI:f():a.cc:1) x"), Some((Level::Error, "x")));
+            assert_eq!(parse_line("VLOG[1] (VoskAPI:f():a.cc:1) debug"), Some((Level::Debug, "debug")));
+            assert_eq!(parse_line("VLOG[3] (VoskAPI:f():a.cc:1) trace"), Some((Level::Trace, "trace")));
+        }
+
+        #[test]
+        fn other_output_passes_through() {
+            assert_eq!(parse_line("thread 'main' panicked"), None);
+            assert_eq!(parse_line("INFO (something) else"), None);
+            assert_eq!(parse_line(""), None);
+        }
+
+        #[test]
+        fn message_without_location() {
+            assert_eq!(parse_line("LOG (VoskAPI:f()"), Some((Level::Info, "(VoskAPI:f()")));
+        }
+    }
+}
@@ -1,20 +1,68 @@
 use std::path::Path;
-use std::ffi::{CStr, CString};
+use std::ffi::CString;
 
+use crate::error::VoskError;
 use crate::ffi;
+use crate::model_info::ModelInfo;
+use crate::recognizer::Recognizer;
 use crate::session::{VoskSession, VoskSessionConfig};
 
+const TRANSCRIBE_CHUNK_SIZE: usize = 4000;
+
 pub struct VoskModel {
     pub(crate) inner: *mut ffi::VoskModel,
 }
 
+// The model is read-only once loaded; Vosk shares it between recognizers on
+// any thread.
+unsafe impl Send for VoskModel {}
+unsafe impl Sync for VoskModel {}
+
 impl VoskModel {
+    /// Does not report failures: a model that failed to load, or whose
+    /// library could not be loaded, crashes the first session. Prefer
+    /// `try_new`.
     pub fn new<P: AsRef<Path>>(root: P) -> Self {
+        #[cfg(feature = "tracing")]
+        let span = tracing::info_span!("vosk_model_load", path = %root.as_ref().display());
+        #[cfg(feature = "tracing")]
+        let _enter = span.enter();
+        #[cfg(feature = "tracing")]
+        let started = std::time::Instant::now();
+
         let root = unsafe { CString::from_vec_unchecked(root.as_ref().to_string_lossy().as_bytes().to_vec()) };
+        let inner = unsafe { ffi::vosk_model_new(root.as_c_str().as_ptr()) };
 
-        Self {
-            inner: unsafe { ffi::vosk_model_new(root.as_c_str().as_ptr()) },
+        #[cfg(feature = "tracing")]
+        tracing::info!(
+            elapsed_ms = started.elapsed().as_millis() as u64,
+            loaded = !inner.is_null(),
+            "model loaded"
+        );
+
+        Self { inner }
+    }
+
+    /// Validates the model directory before loading it and reports failures
+    /// instead of crashing inside Kaldi.
+    pub fn try_new<P: AsRef<Path>>(root: P) -> Result<Self, VoskError> {
+        #[cfg(feature = "dynamic")]
+        ffi::ensure_loaded()?;
+
+        let info = ModelInfo::inspect(root.as_ref());
+        if !info.is_valid() {
+            #[cfg(feature = "tracing")]
+            tracing::warn!(path = %root.as_ref().display(), errors = info.report.errors().count(), "invalid model");
+
+            return Err(VoskError::InvalidModel(info.report));
         }
+
+        let model = Self::new(root.as_ref());
+        if model.inner.is_null() {
+            return Err(VoskError::ModelLoad(root.as_ref().to_path_buf()));
+        }
+
+        Ok(model)
     }
 
     #[inline]
@@ -22,35 +70,37 @@ impl VoskModel {
         VoskSession::new(self.inner, cfg)
     }
 
+    /// Feeds `samples` chunk by chunk and collects every non-empty utterance,
+    /// including the one completed by the final result.
+    pub fn transcribe(&self, cfg: VoskSessionConfig, samples: &[i16]) -> Vec<crate::TranscriptionResult> {
+        self.create_session(cfg).transcribe(samples, TRANSCRIBE_CHUNK_SIZE)
+    }
+
     #[inline]
     pub fn feed(&self, sess: &mut VoskSession, data: &[i16]) -> bool {
-        unsafe { ffi::vosk_recognizer_accept_waveform_s(sess.inner, data.as_ptr(), data.len() as _) == 1 }
+        sess.accept_waveform(data)
     }
 
     #[inline]
     pub fn get_result(&self, sess: &mut VoskSession) -> crate::TranscriptionResult {
-        let cstr = unsafe { CStr::from_ptr(ffi::vosk_recognizer_result(sess.inner)) };
-
-        serde_json::from_str(cstr.to_str().unwrap()).unwrap()
+        sess.result()
     }
 
     #[inline]
     pub fn get_partial_result(&self, sess: &mut VoskSession) -> crate::TranscriptionPartialResult {
-        let cstr = unsafe { CStr::from_ptr(ffi::vosk_recognizer_partial_result(sess.inner)) };
-
-        serde_json::from_str(cstr.to_str().unwrap()).unwrap()
+        sess.partial_result()
     }
 
     #[inline]
-    pub fn get_final_result(&self, sess: VoskSession) -> crate::TranscriptionResult {
-        let cstr = unsafe { CStr::from_ptr(ffi::vosk_recognizer_final_result(sess.inner)) };
-
-        serde_json::from_str(cstr.to_str().unwrap()).unwrap()
+    pub fn get_final_result(&self, mut sess: VoskSession) -> crate::TranscriptionResult {
+        sess.final_result()
     }
 }
 
 impl Drop for VoskModel {
     fn drop(&mut self) {
-        unsafe { ffi::vosk_model_free(self.inner) }
+        if !self.inner.is_null() {
+            unsafe { ffi::vosk_model_free(self.inner) }
+        }
     }
 }
\ No newline at end of file
@@ -0,0 +1,397 @@
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Serialize, Deserialize};
+
+const DEFAULT_SAMPLE_RATE: f32 = 16000.0;
+
+const IVECTOR_FILES: &[&str] = &[
+    "final.dubm",
+    "final.ie",
+    "final.mat",
+    "global_cmvn.stats",
+    "online_cmvn.conf",
+    "splice.conf",
+];
+const RESCORE_FILES: &[&str] = &["G.carpa", "G.fst"];
+const RNNLM_FILES: &[&str] = &["final.raw", "special_symbol_opts.conf", "word_feats.txt"];
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum ModelLayout {
+    /// `am/`, `conf/`, `graph/` subdirectories.
+    Standard,
+    /// Everything in the model root, as in models released before 0.3.
+    Flat,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum GraphKind {
+    /// Precompiled `HCLG.fst`; grammars passed to a session are ignored.
+    Static,
+    /// `HCLr.fst` + `Gr.fst` lookahead graph that can be restricted by a grammar.
+    Lookahead,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ValidationIssue {
+    pub severity: Severity,
+    pub path: PathBuf,
+    pub message: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ValidationReport {
+    pub issues: Vec<ValidationIssue>,
+}
+
+impl ValidationReport {
+    #[inline]
+    pub fn is_valid(&self) -> bool {
+        self.errors().next().is_none()
+    }
+
+    pub fn errors(&self) -> impl Iterator<Item = &ValidationIssue> {
+        self.issues.iter().filter(|i| i.severity == Severity::Error)
+    }
+
+    pub fn warnings(&self) -> impl Iterator<Item = &ValidationIssue> {
+        self.issues.iter().filter(|i| i.severity == Severity::Warning)
+    }
+
+    fn push<P: Into<PathBuf>, M: Into<String>>(&mut self, severity: Severity, path: P, message: M) {
+        self.issues.push(ValidationIssue {
+            severity,
+            path: path.into(),
+            message: message.into(),
+        });
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (idx, issue) in self.issues.iter().enumerate() {
+            if idx > 0 {
+                writeln!(f)?;
+            }
+
+            let severity = match issue.severity {
+                Severity::Warning => "warning",
+                Severity::Error => "error",
+            };
+
+            write!(f, "{}: {}: {}", severity, issue.path.display(), issue.message)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Describes a model directory without loading it.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ModelInfo {
+    pub root: PathBuf,
+    pub layout: Option<ModelLayout>,
+    pub graph: Option<GraphKind>,
+    pub sample_rate: f32,
+    pub has_ivector: bool,
+    pub has_rescore: bool,
+    pub has_rnnlm: bool,
+    pub report: ValidationReport,
+}
+
+impl ModelInfo {
+    pub fn inspect<P: AsRef<Path>>(root: P) -> Self {
+        let root = root.as_ref().to_path_buf();
+        let mut report = ValidationReport::default();
+
+        if !root.is_dir() {
+            report.push(Severity::Error, &root, "model directory does not exist");
+
+            return Self {
+                root,
+                layout: None,
+                graph: None,
+                sample_rate: DEFAULT_SAMPLE_RATE,
+                has_ivector: false,
+                has_rescore: false,
+                has_rnnlm: false,
+                report,
+            };
+        }
+
+        let layout = if root.join("am/final.mdl").is_file() {
+            Some(ModelLayout::Standard)
+        } else if root.join("final.mdl").is_file() {
+            Some(ModelLayout::Flat)
+        } else {
+            report.push(Severity::Error, root.join("am/final.mdl"), "acoustic model is missing");
+            None
+        };
+
+        let (graph_dir, conf_dir) = match layout {
+            Some(ModelLayout::Flat) => (root.clone(), root.clone()),
+            _ => (root.join("graph"), root.join("conf")),
+        };
+
+        let hclg = graph_dir.join("HCLG.fst");
+        let hclr = graph_dir.join("HCLr.fst");
+        let gr = graph_dir.join("Gr.fst");
+
+        let graph = if hclr.is_file() && gr.is_file() {
+            Some(GraphKind::Lookahead)
+        } else if hclg.is_file() {
+            Some(GraphKind::Static)
+        } else {
+            if hclr.is_file() {
+                report.push(Severity::Error, &gr, "HCLr.fst is present but Gr.fst is missing");
+            } else if gr.is_file() {
+                report.push(Severity::Error, &hclr, "Gr.fst is present but HCLr.fst is missing");
+            } else {
+                report.push(Severity::Error, &hclg, "decoding graph is missing (expected HCLG.fst or HCLr.fst + Gr.fst)");
+            }
+            None
+        };
+
+        if !graph_dir.join("words.txt").is_file() {
+            report.push(Severity::Error, graph_dir.join("words.txt"), "word symbol table is missing");
+        }
+
+        if !graph_dir.join("phones/word_boundary.int").is_file() {
+            report.push(Severity::Warning, graph_dir.join("phones/word_boundary.int"), "word boundaries are missing, results will have no word timings");
+        }
+
+        let mfcc_conf = conf_dir.join("mfcc.conf");
+        let sample_rate = if mfcc_conf.is_file() {
+            match read_sample_rate(&mfcc_conf) {
+                Some(rate) => rate,
+                None => {
+                    report.push(Severity::Warning, &mfcc_conf, "no --sample-frequency option, assuming 16000");
+                    DEFAULT_SAMPLE_RATE
+                }
+            }
+        } else {
+            report.push(Severity::Error, &mfcc_conf, "feature configuration is missing");
+            DEFAULT_SAMPLE_RATE
+        };
+
+        let has_ivector = check_optional_dir(&mut report, &root.join("ivector"), IVECTOR_FILES, Severity::Error);
+        let has_rescore = check_optional_dir(&mut report, &root.join("rescore"), RESCORE_FILES, Severity::Warning);
+        let has_rnnlm = check_optional_dir(&mut report, &root.join("rnnlm"), RNNLM_FILES, Severity::Warning);
+
+        if has_rnnlm && !has_rescore {
+            report.push(Severity::Warning, root.join("rnnlm"), "rnnlm rescoring requires rescore/G.carpa and rescore/G.fst");
+        }
+
+        Self {
+            root,
+            layout,
+            graph,
+            sample_rate,
+            has_ivector,
+            has_rescore,
+            has_rnnlm,
+            report,
+        }
+    }
+
+    #[inline]
+    pub fn is_valid(&self) -> bool {
+        self.report.is_valid()
+    }
+
+    /// Whether sessions created with a grammar actually restrict the vocabulary.
+    #[inline]
+    pub fn supports_grammar(&self) -> bool {
+        self.graph == Some(GraphKind::Lookahead)
+    }
+}
+
+fn read_sample_rate(path: &Path) -> Option<f32> {
+    let conf = fs::read_to_string(path).ok()?;
+
+    conf.lines()
+        .filter_map(|line| line.trim().strip_prefix("--sample-frequency="))
+        .next_back()
+        .and_then(|value| value.trim().parse().ok())
+}
+
+/// Checks the files of an optional model component. Returns `true` if the
+/// component is present and complete.
+fn check_optional_dir(report: &mut ValidationReport, dir: &Path, files: &[&str], severity: Severity) -> bool {
+    if !dir.is_dir() {
+        return false;
+    }
+
+    let mut complete = true;
+    for file in files {
+        let path = dir.join(file);
+        if !path.is_file() {
+            report.push(severity, path, "file is missing");
+            complete = false;
+        }
+    }
+
+    complete
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn model(files: &[&str]) -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+
+        for file in files {
+            let path = dir.path().join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+
+            if file.ends_with("mfcc.conf") {
+                fs::write(path, "--use-energy=false\n--sample-frequency=8000\n").unwrap();
+            } else {
+                fs::write(path, "").unwrap();
+            }
+        }
+
+        dir
+    }
+
+    fn issues(info: &ModelInfo) -> Vec<(Severity, PathBuf, &str)> {
+        info.report
+            .issues
+            .iter()
+            .map(|i| (i.severity, i.path.strip_prefix(&info.root).unwrap().to_path_buf(), i.message.as_str()))
+            .collect()
+    }
+
+    const STANDARD: &[&str] = &[
+        "am/final.mdl",
+        "conf/mfcc.conf",
+        "graph/HCLG.fst",
+        "graph/words.txt",
+        "graph/phones/word_boundary.int",
+    ];
+
+    #[test]
+    fn standard_layout() {
+        let dir = model(STANDARD);
+        let info = ModelInfo::inspect(dir.path());
+
+        assert!(info.is_valid());
+        assert!(info.report.issues.is_empty());
+        assert_eq!(info.layout, Some(ModelLayout::Standard));
+        assert_eq!(info.graph, Some(GraphKind::Static));
+        assert!(!info.supports_grammar());
+        assert_eq!(info.sample_rate, 8000.0);
+        assert!(!info.has_ivector && !info.has_rescore && !info.has_rnnlm);
+    }
+
+    #[test]
+    fn flat_layout_with_lookahead_graph() {
+        let dir = model(&["final.mdl", "mfcc.conf", "HCLr.fst", "Gr.fst", "words.txt"]);
+        let info = ModelInfo::inspect(dir.path());
+
+        assert_eq!(info.layout, Some(ModelLayout::Flat));
+        assert_eq!(info.graph, Some(GraphKind::Lookahead));
+        assert!(info.supports_grammar());
+        assert!(info.is_valid());
+        assert_eq!(
+            issues(&info),
+            vec![(
+                Severity::Warning,
+                PathBuf::from("phones/word_boundary.int"),
+                "word boundaries are missing, results will have no word timings"
+            )]
+        );
+    }
+
+    #[test]
+    fn missing_acoustic_model() {
+        let dir = model(&["conf/mfcc.conf", "graph/HCLr.fst", "graph/words.txt", "graph/phones/word_boundary.int"]);
+        let info = ModelInfo::inspect(dir.path());
+
+        assert!(!info.is_valid());
+        assert_eq!(info.layout, None);
+        assert_eq!(info.graph, None);
+        assert_eq!(
+            issues(&info),
+            vec![
+                (Severity::Error, PathBuf::from("am/final.mdl"), "acoustic model is missing"),
+                (Severity::Error, PathBuf::from("graph/Gr.fst"), "HCLr.fst is present but Gr.fst is missing"),
+            ]
+        );
+    }
+
+    #[test]
+    fn missing_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let info = ModelInfo::inspect(dir.path().join("missing"));
+
+        assert!(!info.is_valid());
+        assert_eq!(info.report.to_string(), format!("error: {}: model directory does not exist", info.root.display()));
+    }
+
+    #[test]
+    fn missing_sample_rate() {
+        let dir = model(STANDARD);
+        fs::write(dir.path().join("conf/mfcc.conf"), "--use-energy=false\n").unwrap();
+        let info = ModelInfo::inspect(dir.path());
+
+        assert!(info.is_valid());
+        assert_eq!(info.sample_rate, 16000.0);
+        assert_eq!(info.report.warnings().count(), 1);
+    }
+
+    #[test]
+    fn rescoring_and_rnnlm() {
+        let mut files = STANDARD.to_vec();
+        files.extend(["rescore/G.carpa", "rescore/G.fst", "rnnlm/final.raw", "rnnlm/special_symbol_opts.conf"]);
+        let dir = model(&files);
+        let info = ModelInfo::inspect(dir.path());
+
+        assert!(info.has_rescore);
+        assert!(!info.has_rnnlm);
+        assert!(info.is_valid());
+        assert_eq!(
+            issues(&info),
+            vec![(Severity::Warning, PathBuf::from("rnnlm/word_feats.txt"), "file is missing")]
+        );
+    }
+
+    #[test]
+    fn rnnlm_without_rescoring() {
+        let mut files = STANDARD.to_vec();
+        files.extend(["rnnlm/final.raw", "rnnlm/special_symbol_opts.conf", "rnnlm/word_feats.txt"]);
+        let dir = model(&files);
+        let info = ModelInfo::inspect(dir.path());
+
+        assert!(info.has_rnnlm);
+        assert!(!info.has_rescore);
+        assert_eq!(
+            issues(&info),
+            vec![(
+                Severity::Warning,
+                PathBuf::from("rnnlm"),
+                "rnnlm rescoring requires rescore/G.carpa and rescore/G.fst"
+            )]
+        );
+    }
+
+    #[test]
+    fn incomplete_ivector_extractor() {
+        let mut files = STANDARD.to_vec();
+        files.extend(["ivector/final.dubm", "ivector/final.ie"]);
+        let dir = model(&files);
+        let info = ModelInfo::inspect(dir.path());
+
+        assert!(!info.has_ivector);
+        assert!(!info.is_valid());
+        assert_eq!(info.report.errors().count(), 4);
+    }
+}
@@ -0,0 +1,301 @@
+use crate::transcription::{TranscriptionPartialResult, TranscriptionResult, TranscriptionWord};
+
+// Inflections are listed explicitly, since most stems also start clean
+// words ("arsenal", "prickly", "shiitake", "putativo", "salopette"). A
+// trailing `*` is only used for stems no clean word starts with.
+const EN: &[&str] = &[
+    "arse", "arses", "arsehole", "arseholes", "ass", "asshole*", "bastard", "bastards", "bitch*", "bollocks",
+    "bullshit", "cock", "cocks", "cunt*", "damn", "dick", "dickhead*", "fuck*", "motherfuck*", "piss", "pissed",
+    "prick", "pricks", "shit", "shits", "shitty", "shitting", "shithead", "shitheads", "slut*", "twat", "twats",
+    "wank", "wanker", "wankers", "wanking", "whore*",
+];
+
+const DE: &[&str] = &[
+    "arsch*", "fick*", "fotze*", "hure", "huren", "hurensohn", "miststück*", "scheiß*", "scheiss*", "schlampe*",
+    "wichser*",
+];
+
+const ES: &[&str] = &[
+    "cabrón", "cabrones", "carajo", "coño", "gilipollas", "hostia", "hostias", "joder", "jodido", "jodida",
+    "jodidos", "jodidas", "mierda*", "puta", "putas", "puto", "putos",
+];
+
+const FR: &[&str] = &[
+    "bordel", "bite", "bites", "connard*", "connasse*", "couille*", "enculé*", "merde*", "pétasse*", "pute", "putes",
+    "putain*", "salaud*", "salope", "salopes", "saloperie", "saloperies",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterAction {
+    /// Replaces all but the first letter with the mask character.
+    Mask,
+    Remove,
+    /// Wraps the matched words in the tag markers.
+    Tag,
+}
+
+#[derive(Debug, Clone)]
+struct Term {
+    words: Vec<String>,
+    prefix: bool,
+}
+
+impl Term {
+    fn parse(term: &str) -> Option<Self> {
+        let mut words: Vec<String> = term.split_whitespace().map(str::to_lowercase).collect();
+        let prefix = words.len() == 1 && words[0].ends_with('*');
+
+        if prefix {
+            words[0].pop();
+        }
+
+        if words.is_empty() || words[0].is_empty() {
+            return None;
+        }
+
+        Some(Self { words, prefix })
+    }
+
+    fn matches(&self, words: &[String]) -> bool {
+        if self.prefix {
+            return words.first().is_some_and(|w| w.starts_with(&self.words[0]));
+        }
+
+        words.len() >= self.words.len() && self.words.iter().zip(words).all(|(t, w)| t == w)
+    }
+}
+
+/// Masks, removes or tags blocked words and phrases in results, keeping the
+/// timings of every word left in place.
+#[derive(Debug, Clone)]
+pub struct ProfanityFilter {
+    action: FilterAction,
+    mask: char,
+    tags: (String, String),
+    terms: Vec<Term>,
+}
+
+impl ProfanityFilter {
+    /// A filter with an empty blocklist.
+    pub fn new(action: FilterAction) -> Self {
+        Self {
+            action,
+            mask: '*',
+            tags: ("[".to_string(), "]".to_string()),
+            terms: Vec::new(),
+        }
+    }
+
+    /// A filter with the built-in list for `language`, e.g. "en" or "de-DE";
+    /// languages without a list start empty.
+    pub fn for_language(language: &str, action: FilterAction) -> Self {
+        let mut filter = Self::new(action);
+        filter.add_terms(Self::builtin_terms(language));
+
+        filter
+    }
+
+    /// The built-in list for `language`: "en", "de", "es" or "fr".
+    pub fn builtin_terms(language: &str) -> &'static [&'static str] {
+        let language = language.split(['-', '_']).next().unwrap_or_default().to_lowercase();
+
+        match language.as_str() {
+            "en" => EN,
+            "de" => DE,
+            "es" => ES,
+            "fr" => FR,
+            _ => &[],
+        }
+    }
+
+    #[inline]
+    pub fn set_action(&mut self, action: FilterAction) {
+        self.action = action;
+    }
+
+    #[inline]
+    pub fn set_mask(&mut self, mask: char) {
+        self.mask = mask;
+    }
+
+    #[inline]
+    pub fn set_tags<O: Into<String>, C: Into<String>>(&mut self, open: O, close: C) {
+        self.tags = (open.into(), close.into());
+    }
+
+    /// Blocks a word, a phrase, or with a trailing `*` every word starting
+    /// with it.
+    pub fn add_term(&mut self, term: &str) {
+        if let Some(term) = Term::parse(term) {
+            self.terms.push(term);
+            // Longest phrases first, so they win over their first word.
+            self.terms.sort_by_key(|t| std::cmp::Reverse(t.words.len()));
+        }
+    }
+
+    pub fn add_terms<I, S>(&mut self, terms: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        for term in terms {
+            self.add_term(term.as_ref());
+        }
+    }
+
+    /// Unblocks a term as it was added, e.g. to allow a word of a built-in
+    /// list; returns whether it was blocked.
+    pub fn remove_term(&mut self, term: &str) -> bool {
+        let term = match Term::parse(term) {
+            Some(term) => term,
+            None => return false,
+        };
+
+        let len = self.terms.len();
+        self.terms.retain(|t| t.words != term.words || t.prefix != term.prefix);
+
+        self.terms.len() != len
+    }
+
+    pub fn clear(&mut self) {
+        self.terms.clear();
+    }
+
+    pub fn filter(&self, result: &TranscriptionResult) -> TranscriptionResult {
+        result.with_tokens(self.filter_words(&result.tokens()))
+    }
+
+    pub fn filter_partial(&self, partial: &TranscriptionPartialResult) -> TranscriptionPartialResult {
+        let result = TranscriptionResult {
+            text: partial.partial.clone(),
+            ..Default::default()
+        };
+
+        TranscriptionPartialResult {
+            partial: self.filter(&result).text,
+        }
+    }
+
+    pub fn filter_words(&self, words: &[TranscriptionWord]) -> Vec<TranscriptionWord> {
+        // Matched without case and surrounding punctuation, so the filter may
+        // run after punctuation restoration.
+        let keys: Vec<String> = words
+            .iter()
+            .map(|w| w.word.trim_matches(|c: char| !c.is_alphanumeric()).to_lowercase())
+            .collect();
+
+        let mut out = Vec::with_capacity(words.len());
+        let mut idx = 0;
+
+        while idx < words.len() {
+            let len = match self.terms.iter().find(|t| t.matches(&keys[idx..])) {
+                Some(term) if term.prefix => 1,
+                Some(term) => term.words.len(),
+                None => {
+                    out.push(words[idx].clone());
+                    idx += 1;
+                    continue;
+                }
+            };
+
+            let matched = &words[idx..idx + len];
+            match self.action {
+                FilterAction::Remove => {}
+                FilterAction::Mask => out.extend(matched.iter().map(|w| TranscriptionWord {
+                    word: self.mask_word(&w.word),
+                    ..w.clone()
+                })),
+                FilterAction::Tag => {
+                    let first = out.len();
+                    out.extend(matched.iter().cloned());
+                    out[first].word.insert_str(0, &self.tags.0);
+                    // Before trailing punctuation: "[shit]," rather than "[shit,]".
+                    let last = &mut out.last_mut().unwrap().word;
+                    let end = last.trim_end_matches(|c: char| !c.is_alphanumeric()).len();
+                    last.insert_str(end, &self.tags.1);
+                }
+            }
+
+            idx += len;
+        }
+
+        out
+    }
+
+    fn mask_word(&self, word: &str) -> String {
+        let mut seen_letter = false;
+
+        word.chars()
+            .map(|c| {
+                if !c.is_alphanumeric() {
+                    c
+                } else if !seen_letter {
+                    seen_letter = true;
+                    c
+                } else {
+                    self.mask
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filter(filter: &ProfanityFilter, text: &str) -> String {
+        let result = TranscriptionResult {
+            text: text.to_string(),
+            ..Default::default()
+        };
+
+        filter.filter(&result).text
+    }
+
+    #[test]
+    fn builtin_lists_skip_clean_words() {
+        let en = ProfanityFilter::for_language("en", FilterAction::Mask);
+        for clean in ["arsenal", "arsenic", "prickly", "prickle", "shitake", "shiitake", "twattle", "wankel", "passing"] {
+            assert_eq!(filter(&en, clean), clean);
+        }
+
+        let es = ProfanityFilter::for_language("es", FilterAction::Mask);
+        for clean in ["putativo", "hostiario"] {
+            assert_eq!(filter(&es, clean), clean);
+        }
+
+        let fr = ProfanityFilter::for_language("fr-FR", FilterAction::Mask);
+        for clean in ["salopette", "biteau", "putéal"] {
+            assert_eq!(filter(&fr, clean), clean);
+        }
+    }
+
+    #[test]
+    fn builtin_lists_match_inflections() {
+        let en = ProfanityFilter::for_language("en-US", FilterAction::Mask);
+        assert_eq!(filter(&en, "arse arsehole fucking shitty"), "a*** a******* f****** s*****");
+
+        let es = ProfanityFilter::for_language("es", FilterAction::Mask);
+        assert_eq!(filter(&es, "putas jodidos"), "p**** j******");
+    }
+
+    #[test]
+    fn actions() {
+        let mut filter_ = ProfanityFilter::new(FilterAction::Remove);
+        filter_.add_terms(["darn", "heck*", "gosh darn it"]);
+        assert_eq!(filter(&filter_, "well darn, heckin gosh darn it"), "well");
+
+        filter_.set_action(FilterAction::Tag);
+        filter_.set_tags("<", ">");
+        assert_eq!(filter(&filter_, "oh darn, gosh darn it"), "oh <darn>, <gosh darn it>");
+
+        filter_.set_action(FilterAction::Mask);
+        filter_.set_mask('#');
+        assert_eq!(filter(&filter_, "Darn."), "D###.");
+
+        assert!(filter_.remove_term("heck*"));
+        assert!(!filter_.remove_term("heck"));
+        assert_eq!(filter(&filter_, "heckin"), "heckin");
+    }
+}
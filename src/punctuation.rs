@@ -0,0 +1,285 @@
+use crate::transcription::{TranscriptionResult, TranscriptionWord};
+
+const QUESTION_WORDS: [&str; 9] = ["what", "who", "whom", "whose", "where", "when", "why", "how", "which"];
+
+// Questions only when followed by a subject, since "do it now" or "can of
+// beans" start statements too.
+const AUXILIARIES: [&str; 24] = [
+    "am", "is", "are", "was", "were", "do", "does", "did", "can", "could", "will", "would", "should", "shall", "may",
+    "might", "have", "has", "had", "isn't", "aren't", "don't", "doesn't", "didn't",
+];
+
+const SUBJECTS: [&str; 12] = ["i", "you", "he", "she", "it", "we", "they", "there", "this", "that", "the", "anyone"];
+
+/// Restores sentence punctuation and capitalization from the pauses between
+/// words: long pauses and the end of an utterance end a sentence, shorter
+/// ones become commas.
+#[derive(Debug, Clone)]
+pub struct Punctuator {
+    sentence_pause: f32,
+    comma_pause: f32,
+    proper_nouns: Vec<Vec<String>>,
+}
+
+impl Default for Punctuator {
+    fn default() -> Self {
+        Self {
+            sentence_pause: 0.8,
+            comma_pause: 0.35,
+            proper_nouns: Vec::new(),
+        }
+    }
+}
+
+impl Punctuator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Pause in seconds that ends a sentence, 0.8 by default.
+    #[inline]
+    pub fn set_sentence_pause(&mut self, seconds: f32) {
+        self.sentence_pause = seconds;
+    }
+
+    /// Pause in seconds that inserts a comma, 0.35 by default.
+    #[inline]
+    pub fn set_comma_pause(&mut self, seconds: f32) {
+        self.comma_pause = seconds;
+    }
+
+    /// Capitalizes `noun` as given wherever its words occur, e.g. "New York"
+    /// or "McDonald".
+    pub fn add_proper_noun<S: AsRef<str>>(&mut self, noun: S) {
+        let words: Vec<String> = noun.as_ref().split_whitespace().map(str::to_string).collect();
+        if words.is_empty() {
+            return;
+        }
+
+        self.proper_nouns.push(words);
+        // Longest first, so "new york city" wins over "new york".
+        self.proper_nouns.sort_by_key(|noun| std::cmp::Reverse(noun.len()));
+    }
+
+    pub fn add_proper_nouns<I, S>(&mut self, nouns: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        for noun in nouns {
+            self.add_proper_noun(noun);
+        }
+    }
+
+    /// Punctuates one utterance; without word timings only the end of the
+    /// utterance is a sentence break.
+    pub fn punctuate(&self, result: &TranscriptionResult) -> TranscriptionResult {
+        result.with_tokens(self.punctuate_words(&result.tokens()))
+    }
+
+    pub fn punctuate_text(&self, text: &str) -> String {
+        let result = TranscriptionResult {
+            text: text.to_string(),
+            ..Default::default()
+        };
+
+        self.punctuate(&result).text
+    }
+
+    pub fn punctuate_words(&self, words: &[TranscriptionWord]) -> Vec<TranscriptionWord> {
+        let mut words = words.to_vec();
+        if words.is_empty() {
+            return words;
+        }
+
+        self.capitalize_proper_nouns(&mut words);
+
+        let mut sentence_start = 0;
+        for idx in 0..words.len() {
+            let pause = match words.get(idx + 1) {
+                Some(next) => next.start - words[idx].end,
+                None => f32::INFINITY,
+            };
+
+            if pause >= self.sentence_pause {
+                let question = is_question(&words[sentence_start..=idx]);
+                append(&mut words[idx].word, if question { '?' } else { '.' });
+                capitalize(&mut words[sentence_start].word);
+                sentence_start = idx + 1;
+            } else if pause >= self.comma_pause {
+                append(&mut words[idx].word, ',');
+            }
+        }
+
+        words
+    }
+
+    fn capitalize_proper_nouns(&self, words: &mut [TranscriptionWord]) {
+        let mut idx = 0;
+
+        while idx < words.len() {
+            let noun = self.proper_nouns.iter().find(|noun| {
+                words.len() - idx >= noun.len()
+                    && noun
+                        .iter()
+                        .zip(&words[idx..])
+                        .all(|(n, w)| n.eq_ignore_ascii_case(&w.word))
+            });
+
+            match noun {
+                Some(noun) => {
+                    for (n, w) in noun.iter().zip(&mut words[idx..]) {
+                        w.word = n.clone();
+                    }
+                    idx += noun.len();
+                }
+                None => {
+                    let word = &mut words[idx].word;
+                    if word == "i" || word.starts_with("i'") {
+                        capitalize(word);
+                    }
+                    idx += 1;
+                }
+            }
+        }
+    }
+}
+
+fn is_question(sentence: &[TranscriptionWord]) -> bool {
+    let first = sentence[0].word.to_lowercase();
+    if QUESTION_WORDS.contains(&first.as_str()) {
+        return true;
+    }
+
+    let subject = match sentence.get(1) {
+        Some(word) => word.word.to_lowercase(),
+        None => return false,
+    };
+
+    // "do it now" and "do this" are commands.
+    if first == "do" {
+        return ["i", "you", "we", "they"].contains(&subject.as_str());
+    }
+
+    AUXILIARIES.contains(&first.as_str()) && SUBJECTS.contains(&subject.as_str())
+}
+
+/// Adds `mark` unless the word already ends in punctuation, e.g. after
+/// inverse text normalization.
+fn append(word: &mut String, mark: char) {
+    if !word.ends_with(['.', '?', '!', ',', ';', ':']) {
+        word.push(mark);
+    }
+}
+
+fn capitalize(word: &mut String) {
+    if let Some(first) = word.chars().next() {
+        if first.is_lowercase() {
+            let upper: String = first.to_uppercase().collect();
+            word.replace_range(..first.len_utf8(), &upper);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn timed(words: &[(&str, f32, f32)]) -> TranscriptionResult {
+        TranscriptionResult {
+            text: words.iter().map(|w| w.0).collect::<Vec<_>>().join(" "),
+            result: words
+                .iter()
+                .map(|&(word, start, end)| TranscriptionWord {
+                    conf: 0.9,
+                    start,
+                    end,
+                    word: word.to_string(),
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn pauses_become_commas_and_sentence_breaks() {
+        let result = timed(&[
+            ("well", 0.0, 0.25),
+            ("hello", 0.75, 1.0),
+            ("there", 1.25, 1.5),
+            ("nice", 2.5, 2.75),
+            ("weather", 3.0, 3.25),
+        ]);
+
+        // 0.5 s is a comma, 0.25 s nothing, 1 s a sentence break.
+        assert_eq!(Punctuator::new().punctuate(&result).text, "Well, hello there. Nice weather.");
+    }
+
+    #[test]
+    fn custom_pauses() {
+        let result = timed(&[("one", 0.0, 0.5), ("two", 0.7, 1.0), ("three", 1.5, 2.0)]);
+
+        let mut punctuator = Punctuator::new();
+        punctuator.set_comma_pause(0.1);
+        punctuator.set_sentence_pause(0.5);
+
+        assert_eq!(punctuator.punctuate(&result).text, "One, two. Three.");
+    }
+
+    #[test]
+    fn keeps_word_timings() {
+        let mut result = timed(&[("hello", 0.0, 0.4), ("world", 1.5, 2.0)]);
+        result.spk = Some(vec![0.5]);
+
+        let punctuated = Punctuator::new().punctuate(&result);
+        assert_eq!(punctuated.text, "Hello. World.");
+        assert_eq!(punctuated.spk, Some(vec![0.5]));
+
+        let words: Vec<_> = punctuated.result.iter().map(|w| (w.word.as_str(), w.start, w.end)).collect();
+        assert_eq!(words, vec![("Hello.", 0.0, 0.4), ("World.", 1.5, 2.0)]);
+    }
+
+    #[test]
+    fn text_without_timings_is_one_sentence() {
+        let result = TranscriptionResult {
+            text: "so i think i'm done".to_string(),
+            ..Default::default()
+        };
+
+        let punctuated = Punctuator::new().punctuate(&result);
+        assert_eq!(punctuated.text, "So I think I'm done.");
+        assert!(punctuated.result.is_empty());
+        assert_eq!(Punctuator::new().punctuate_text(""), "");
+    }
+
+    #[test]
+    fn questions() {
+        let punctuator = Punctuator::new();
+
+        assert_eq!(punctuator.punctuate_text("where is it"), "Where is it?");
+        assert_eq!(punctuator.punctuate_text("is it raining"), "Is it raining?");
+        assert_eq!(punctuator.punctuate_text("do you know"), "Do you know?");
+        assert_eq!(punctuator.punctuate_text("do it now"), "Do it now.");
+        assert_eq!(punctuator.punctuate_text("can of beans"), "Can of beans.");
+        assert_eq!(punctuator.punctuate_text("is"), "Is.");
+    }
+
+    #[test]
+    fn existing_punctuation_is_kept() {
+        let result = timed(&[("apples", 0.0, 0.5), ("etc.", 0.5, 1.0), ("and", 1.5, 1.75), ("pears", 1.75, 2.0)]);
+
+        assert_eq!(Punctuator::new().punctuate(&result).text, "Apples etc. and pears.");
+        assert_eq!(Punctuator::new().punctuate_text("really?"), "Really?");
+    }
+
+    #[test]
+    fn proper_nouns() {
+        let mut punctuator = Punctuator::new();
+        punctuator.add_proper_nouns(["New York", "New York City", "McDonald", " "]);
+
+        assert_eq!(
+            punctuator.punctuate_text("mcdonald moved to new york city from new york"),
+            "McDonald moved to New York City from New York."
+        );
+    }
+}
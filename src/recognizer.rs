@@ -0,0 +1,371 @@
+use std::time::Instant;
+
+use crate::session::VoskSession;
+use crate::stats::{SessionStats, StatsRecorder};
+use crate::transcription::{TranscriptionPartialResult, TranscriptionResult};
+
+/// The streaming operations of a recognizer, so code driving one can be
+/// tested against `FakeRecognizer` instead of a loaded model.
+pub trait Recognizer {
+    /// Feeds 16-bit mono samples, returns true once an utterance is complete
+    /// and `result` holds it.
+    fn accept(&mut self, samples: &[i16]) -> bool;
+
+    fn result(&mut self) -> TranscriptionResult;
+
+    fn partial_result(&mut self) -> TranscriptionPartialResult;
+
+    /// Flushes the remaining audio into a last result; the recognizer can
+    /// be fed again afterwards.
+    fn final_result(&mut self) -> TranscriptionResult;
+
+    /// Drops the utterance in progress.
+    fn reset(&mut self);
+
+    /// Feeds `samples` in chunks of `chunk_size` and collects every
+    /// non-empty utterance, including the one completed by the final result.
+    fn transcribe(&mut self, samples: &[i16], chunk_size: usize) -> Vec<TranscriptionResult> {
+        let mut results = Vec::new();
+
+        for chunk in samples.chunks(chunk_size.max(1)) {
+            if self.accept(chunk) {
+                results.push(self.result());
+            }
+        }
+
+        results.push(self.final_result());
+        results.retain(|r| !r.text.is_empty());
+
+        results
+    }
+}
+
+impl Recognizer for VoskSession {
+    #[inline]
+    fn accept(&mut self, samples: &[i16]) -> bool {
+        self.accept_waveform(samples)
+    }
+
+    #[inline]
+    fn result(&mut self) -> TranscriptionResult {
+        VoskSession::result(self)
+    }
+
+    #[inline]
+    fn partial_result(&mut self) -> TranscriptionPartialResult {
+        VoskSession::partial_result(self)
+    }
+
+    #[inline]
+    fn final_result(&mut self) -> TranscriptionResult {
+        VoskSession::final_result(self)
+    }
+
+    #[inline]
+    fn reset(&mut self) {
+        self.discard();
+    }
+}
+
+#[derive(Debug, Clone)]
+enum ScriptEvent {
+    Partial(String),
+    Result(TranscriptionResult),
+}
+
+pub struct FakeRecognizerBuilder {
+    events: Vec<(usize, ScriptEvent)>,
+    final_result: Option<TranscriptionResult>,
+    sample_rate: f32,
+}
+
+impl FakeRecognizerBuilder {
+    fn new() -> Self {
+        Self {
+            events: Vec::new(),
+            final_result: None,
+            sample_rate: 16000.0,
+        }
+    }
+
+    /// Used for the seconds of audio in `FakeRecognizer::stats`, 16000 by default.
+    pub fn sample_rate(&mut self, sample_rate: f32) -> &mut Self {
+        self.sample_rate = sample_rate;
+        self
+    }
+
+    /// Makes `text` the partial result once `position` samples were fed.
+    pub fn partial<T: Into<String>>(&mut self, position: usize, text: T) -> &mut Self {
+        self.events.push((position, ScriptEvent::Partial(text.into())));
+        self
+    }
+
+    /// Completes an utterance with `result` once `position` samples were fed.
+    pub fn result(&mut self, position: usize, result: TranscriptionResult) -> &mut Self {
+        self.events.push((position, ScriptEvent::Result(result)));
+        self
+    }
+
+    /// Returned by the next `final_result` that has no pending utterance;
+    /// without one the current partial text is returned.
+    pub fn final_result(&mut self, result: TranscriptionResult) -> &mut Self {
+        self.final_result = Some(result);
+        self
+    }
+
+    pub fn finish(&mut self) -> FakeRecognizer {
+        let mut events = core::mem::take(&mut self.events);
+        events.sort_by_key(|(position, _)| *position);
+
+        FakeRecognizer {
+            events,
+            next_event: 0,
+            position: 0,
+            partial: String::new(),
+            pending: None,
+            final_result: core::mem::take(&mut self.final_result),
+            sample_rate: self.sample_rate,
+            stats: StatsRecorder::default(),
+        }
+    }
+}
+
+/// Replays scripted results keyed on the number of samples fed so far,
+/// counted across utterances and resets.
+pub struct FakeRecognizer {
+    events: Vec<(usize, ScriptEvent)>,
+    next_event: usize,
+    position: usize,
+    partial: String,
+    pending: Option<TranscriptionResult>,
+    final_result: Option<TranscriptionResult>,
+    sample_rate: f32,
+    stats: StatsRecorder,
+}
+
+impl FakeRecognizer {
+    pub fn builder() -> FakeRecognizerBuilder {
+        FakeRecognizerBuilder::new()
+    }
+
+    /// Number of samples fed so far.
+    #[inline]
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// True once every scripted partial and result was replayed.
+    #[inline]
+    pub fn is_exhausted(&self) -> bool {
+        self.next_event == self.events.len()
+    }
+
+    /// Counted like `VoskSession::stats`.
+    #[inline]
+    pub fn stats(&self) -> SessionStats {
+        self.stats.stats()
+    }
+}
+
+impl Recognizer for FakeRecognizer {
+    fn accept(&mut self, samples: &[i16]) -> bool {
+        let started = Instant::now();
+        self.position += samples.len();
+
+        // Like the real recognizer, at most one utterance completes per call;
+        // later events are replayed by the next one.
+        while self.pending.is_none() {
+            match self.events.get(self.next_event) {
+                Some((position, event)) if *position <= self.position => {
+                    match event.clone() {
+                        ScriptEvent::Partial(text) => self.partial = text,
+                        ScriptEvent::Result(result) => {
+                            self.partial.clear();
+                            self.pending = Some(result);
+                        }
+                    }
+                    self.next_event += 1;
+                }
+                _ => break,
+            }
+        }
+
+        self.stats.accept(samples.len(), self.sample_rate, started, Instant::now());
+
+        self.pending.is_some()
+    }
+
+    fn result(&mut self) -> TranscriptionResult {
+        let result = self.pending.take().unwrap_or_default();
+        self.stats.result(&result);
+
+        result
+    }
+
+    fn partial_result(&mut self) -> TranscriptionPartialResult {
+        self.stats.partial(&self.partial);
+
+        TranscriptionPartialResult {
+            partial: self.partial.clone(),
+        }
+    }
+
+    fn final_result(&mut self) -> TranscriptionResult {
+        let partial = core::mem::take(&mut self.partial);
+
+        let result = self.pending.take().or_else(|| self.final_result.take()).unwrap_or(TranscriptionResult {
+            text: partial,
+            ..Default::default()
+        });
+        self.stats.final_result(&result);
+
+        result
+    }
+
+    fn reset(&mut self) {
+        self.partial.clear();
+        self.pending = None;
+        self.stats.discard();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(text: &str) -> TranscriptionResult {
+        TranscriptionResult {
+            text: text.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn one_result_per_accept() {
+        let mut fake = FakeRecognizer::builder()
+            .result(100, result("one"))
+            .result(150, result("two"))
+            .finish();
+
+        assert!(fake.accept(&[0; 200]));
+        assert_eq!(fake.result().text, "one");
+        assert!(!fake.is_exhausted());
+
+        // The second utterance was already due and completes on the next call.
+        assert!(fake.accept(&[]));
+        assert_eq!(fake.result().text, "two");
+        assert!(fake.is_exhausted());
+
+        assert!(!fake.accept(&[0; 100]));
+        assert_eq!(fake.result().text, "");
+        assert_eq!(fake.position(), 300);
+    }
+
+    #[test]
+    fn partials_follow_position() {
+        let mut fake = FakeRecognizer::builder()
+            .partial(80, "hello")
+            .partial(50, "he")
+            .result(100, result("hello world"))
+            .finish();
+
+        assert!(!fake.accept(&[0; 60]));
+        assert_eq!(fake.partial_result().partial, "he");
+        assert!(!fake.accept(&[0; 30]));
+        assert_eq!(fake.partial_result().partial, "hello");
+
+        assert!(fake.accept(&[0; 10]));
+        assert_eq!(fake.partial_result().partial, "");
+        assert_eq!(fake.result().text, "hello world");
+    }
+
+    #[test]
+    fn final_result_fallbacks() {
+        // A completed but unfetched utterance comes first.
+        let mut fake = FakeRecognizer::builder()
+            .result(10, result("pending"))
+            .final_result(result("final"))
+            .finish();
+        assert!(fake.accept(&[0; 10]));
+        assert_eq!(fake.final_result().text, "pending");
+        assert_eq!(fake.final_result().text, "final");
+        assert_eq!(fake.final_result().text, "");
+
+        // Without a scripted final result, the partial text is flushed.
+        let mut fake = FakeRecognizer::builder().partial(5, "half a").finish();
+        fake.accept(&[0; 5]);
+        assert_eq!(fake.final_result().text, "half a");
+        assert_eq!(fake.partial_result().partial, "");
+    }
+
+    #[test]
+    fn reset_drops_the_utterance() {
+        let mut fake = FakeRecognizer::builder()
+            .partial(5, "dropped")
+            .result(10, result("dropped too"))
+            .result(20, result("kept"))
+            .finish();
+
+        fake.accept(&[0; 5]);
+        fake.reset();
+        assert_eq!(fake.partial_result().partial, "");
+
+        assert!(fake.accept(&[0; 5]));
+        fake.reset();
+        assert_eq!(fake.result().text, "");
+        assert_eq!(fake.position(), 10);
+
+        // Positions keep counting across resets.
+        assert!(fake.accept(&[0; 10]));
+        assert_eq!(fake.result().text, "kept");
+        assert!(fake.is_exhausted());
+    }
+
+    #[test]
+    fn reset_leaves_stats_alone() {
+        let mut fake = FakeRecognizer::builder()
+            .sample_rate(1000.0)
+            .partial(100, "hel")
+            .partial(200, "hello")
+            .result(300, result("hello"))
+            .finish();
+
+        fake.accept(&[0; 100]);
+        fake.partial_result();
+        fake.partial_result();
+        fake.accept(&[0; 100]);
+        fake.partial_result();
+        assert_eq!(fake.stats().partial_updates, 2);
+
+        let before = fake.stats();
+        fake.reset();
+        assert_eq!(fake.stats(), before);
+
+        assert!(fake.accept(&[0; 100]));
+        assert_eq!(fake.result().text, "hello");
+        assert_eq!(fake.final_result().text, "");
+
+        let stats = fake.stats();
+        assert_eq!(stats.accept_calls, 3);
+        assert!((stats.audio_seconds - 0.3).abs() < 1e-9);
+        assert_eq!(stats.utterances, 1);
+        assert_eq!(stats.partial_updates, 2);
+        assert!(stats.final_latency.is_some());
+
+        fake.reset();
+        assert_eq!(fake.stats(), stats);
+    }
+
+    #[test]
+    fn transcribe_collects_utterances() {
+        let mut fake = FakeRecognizer::builder()
+            .result(4, result("first"))
+            .result(6, result(""))
+            .partial(9, "last")
+            .finish();
+
+        let texts: Vec<String> = fake.transcribe(&[0; 10], 3).into_iter().map(|r| r.text).collect();
+
+        assert_eq!(texts, ["first", "last"]);
+    }
+}
@@ -0,0 +1,382 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::error::VoskError;
+use crate::model::VoskModel;
+use crate::model_info::ModelInfo;
+
+// Language codes used in Vosk model names, including the non-standard "cn",
+// "gr", "kz", "ua" and "vn".
+const LANGUAGES: &[&str] = &[
+    "ar", "br", "ca", "cn", "cs", "de", "el", "en", "eo", "es", "fa", "fr", "gr", "gu", "hi", "it", "ja", "ko", "ky",
+    "kz", "nl", "pl", "pt", "ru", "sv", "te", "tg", "tl", "tr", "ua", "uk", "uz", "vn", "zh",
+];
+
+#[derive(Debug, Clone)]
+pub struct ModelEntry {
+    pub name: String,
+    pub language: Option<String>,
+    pub path: PathBuf,
+    /// Size of the model directory on disk, used as the memory estimate.
+    pub size: u64,
+    pub info: ModelInfo,
+}
+
+impl ModelEntry {
+    /// Whether the model is for `language` or a regional variant of it, so
+    /// `"en"` also matches `"en-us"`.
+    pub fn matches_language(&self, language: &str) -> bool {
+        let language = normalize_language(language);
+
+        match self.language.as_deref() {
+            Some(l) => l == language || (l.starts_with(&language) && l[language.len()..].starts_with('-')),
+            None => false,
+        }
+    }
+}
+
+struct LoadedModel {
+    model: Arc<VoskModel>,
+    last_used: Instant,
+}
+
+struct Slot {
+    entry: ModelEntry,
+    loaded: Mutex<Option<LoadedModel>>,
+}
+
+/// Indexes the models of a directory by name and language and loads them on
+/// first use. Loaded models are shared across threads.
+pub struct ModelRegistry {
+    slots: Vec<Slot>,
+    memory_budget: Option<u64>,
+}
+
+impl ModelRegistry {
+    pub fn new() -> Self {
+        Self {
+            slots: Vec::new(),
+            memory_budget: None,
+        }
+    }
+
+    /// Registers every valid model found directly under `dir`.
+    pub fn scan<P: AsRef<Path>>(dir: P) -> Result<Self, VoskError> {
+        let mut registry = Self::new();
+
+        let mut paths = Vec::new();
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                paths.push(path);
+            }
+        }
+        paths.sort();
+
+        for path in paths {
+            let info = ModelInfo::inspect(&path);
+            if !info.is_valid() {
+                continue;
+            }
+
+            let name = path.file_name().unwrap().to_string_lossy().into_owned();
+            let language = guess_language(&name);
+            registry.insert(name, language, info);
+        }
+
+        Ok(registry)
+    }
+
+    /// Registers a single model under an explicit name and language.
+    pub fn register<N, L, P>(&mut self, name: N, language: L, path: P) -> Result<(), VoskError>
+    where
+        N: Into<String>,
+        L: Into<String>,
+        P: AsRef<Path>,
+    {
+        let info = ModelInfo::inspect(path);
+        if !info.is_valid() {
+            return Err(VoskError::InvalidModel(info.report));
+        }
+
+        self.insert(name.into(), Some(language.into()), info);
+
+        Ok(())
+    }
+
+    /// Models are unloaded, least recently used first, to keep the total
+    /// on-disk size of loaded models under `bytes`.
+    #[inline]
+    pub fn set_memory_budget(&mut self, bytes: u64) {
+        self.memory_budget = Some(bytes);
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = &ModelEntry> {
+        self.slots.iter().map(|s| &s.entry)
+    }
+
+    pub fn entry(&self, name: &str) -> Option<&ModelEntry> {
+        self.slot(name).map(|s| &s.entry)
+    }
+
+    pub fn by_language<'a>(&'a self, language: &'a str) -> impl Iterator<Item = &'a ModelEntry> + 'a {
+        self.entries()
+            .filter(move |e| e.matches_language(language))
+    }
+
+    pub fn get(&self, name: &str) -> Result<Arc<VoskModel>, VoskError> {
+        let slot = self
+            .slot(name)
+            .ok_or_else(|| VoskError::UnknownModel(name.to_string()))?;
+
+        self.load(slot)
+    }
+
+    /// Returns a model for `language`, preferring one that is already loaded.
+    pub fn get_for_language(&self, language: &str) -> Result<Arc<VoskModel>, VoskError> {
+        let candidates: Vec<&Slot> = self
+            .slots
+            .iter()
+            .filter(|s| s.entry.matches_language(language))
+            .collect();
+
+        let slot = candidates
+            .iter()
+            .find(|s| s.loaded.lock().unwrap().is_some())
+            .or_else(|| candidates.first())
+            .ok_or_else(|| VoskError::UnknownModel(language.to_string()))?;
+
+        self.load(slot)
+    }
+
+    pub fn is_loaded(&self, name: &str) -> bool {
+        self.slot(name)
+            .is_some_and(|s| s.loaded.lock().unwrap().is_some())
+    }
+
+    /// Drops the registry's reference to a model. Sessions still holding the
+    /// model keep it alive until they are done.
+    pub fn unload(&self, name: &str) -> bool {
+        match self.slot(name) {
+            Some(slot) => slot.loaded.lock().unwrap().take().is_some(),
+            None => false,
+        }
+    }
+
+    /// Unloads models that are not in use and have not been requested for
+    /// `max_idle`. Returns the number of unloaded models.
+    pub fn unload_idle(&self, max_idle: Duration) -> usize {
+        let mut count = 0;
+
+        for slot in &self.slots {
+            let mut loaded = slot.loaded.lock().unwrap();
+            let idle = loaded
+                .as_ref()
+                .is_some_and(|l| Arc::strong_count(&l.model) == 1 && l.last_used.elapsed() >= max_idle);
+
+            if idle {
+                *loaded = None;
+                count += 1;
+            }
+        }
+
+        count
+    }
+
+    /// Total on-disk size of the currently loaded models.
+    pub fn loaded_size(&self) -> u64 {
+        self.slots
+            .iter()
+            .filter(|s| s.loaded.lock().unwrap().is_some())
+            .map(|s| s.entry.size)
+            .sum()
+    }
+
+    fn insert(&mut self, name: String, language: Option<String>, info: ModelInfo) {
+        self.slots.retain(|s| s.entry.name != name);
+        self.slots.push(Slot {
+            entry: ModelEntry {
+                name,
+                language: language.as_deref().map(normalize_language),
+                path: info.root.clone(),
+                size: dir_size(&info.root),
+                info,
+            },
+            loaded: Mutex::new(None),
+        });
+    }
+
+    fn slot(&self, name: &str) -> Option<&Slot> {
+        self.slots.iter().find(|s| s.entry.name == name)
+    }
+
+    fn load(&self, slot: &Slot) -> Result<Arc<VoskModel>, VoskError> {
+        let mut loaded = slot.loaded.lock().unwrap();
+
+        if let Some(loaded) = loaded.as_mut() {
+            loaded.last_used = Instant::now();
+            return Ok(loaded.model.clone());
+        }
+
+        if let Some(budget) = self.memory_budget {
+            self.evict(slot, budget.saturating_sub(slot.entry.size));
+        }
+
+        let model = Arc::new(VoskModel::try_new(&slot.entry.path)?);
+        *loaded = Some(LoadedModel {
+            model: model.clone(),
+            last_used: Instant::now(),
+        });
+
+        Ok(model)
+    }
+
+    /// Unloads idle models until the loaded size fits in `target`. Slots that
+    /// are busy loading are skipped rather than waited on.
+    fn evict(&self, current: &Slot, target: u64) {
+        let mut used = 0;
+        let mut candidates = Vec::new();
+
+        for slot in &self.slots {
+            if std::ptr::eq(slot, current) {
+                continue;
+            }
+
+            if let Ok(loaded) = slot.loaded.try_lock() {
+                if let Some(l) = loaded.as_ref() {
+                    used += slot.entry.size;
+                    if Arc::strong_count(&l.model) == 1 {
+                        candidates.push((l.last_used, slot));
+                    }
+                }
+            }
+        }
+
+        candidates.sort_by_key(|(last_used, _)| *last_used);
+
+        for (_, slot) in candidates {
+            if used <= target {
+                break;
+            }
+
+            if let Ok(mut loaded) = slot.loaded.try_lock() {
+                if loaded.take().is_some() {
+                    used -= slot.entry.size;
+                }
+            }
+        }
+    }
+}
+
+impl Default for ModelRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Guesses the language tag from names like `vosk-model-small-en-us-0.15`
+/// or `en-small`.
+fn guess_language(name: &str) -> Option<String> {
+    let name = name.to_lowercase();
+    let name = name.strip_prefix("vosk-model-").unwrap_or(&name);
+    let parts: Vec<&str> = name
+        .split(['-', '_'])
+        .filter(|p| *p != "small")
+        .collect();
+
+    let idx = parts.iter().position(|p| LANGUAGES.contains(p))?;
+
+    match parts.get(idx + 1) {
+        Some(region) if region.len() == 2 && region.chars().all(|c| c.is_ascii_alphabetic()) => {
+            Some(format!("{}-{}", parts[idx], region))
+        }
+        _ => Some(parts[idx].to_string()),
+    }
+}
+
+/// Lowercase with `-` separators, so "en_US" and "en-us" are the same.
+fn normalize_language(language: &str) -> String {
+    language.to_lowercase().replace('_', "-")
+}
+
+fn dir_size(path: &Path) -> u64 {
+    let entries = match fs::read_dir(path) {
+        Ok(entries) => entries,
+        Err(_) => return 0,
+    };
+
+    entries
+        .filter_map(Result::ok)
+        .map(|entry| match entry.metadata() {
+            Ok(meta) if meta.is_dir() => dir_size(&entry.path()),
+            Ok(meta) => meta.len(),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn registry(languages: &[(&str, Option<&str>)]) -> ModelRegistry {
+        let mut registry = ModelRegistry::new();
+        for (name, language) in languages {
+            let info = ModelInfo::inspect(Path::new("/nonexistent").join(name));
+            registry.insert(name.to_string(), language.map(str::to_string), info);
+        }
+
+        registry
+    }
+
+    fn names<'a>(registry: &'a ModelRegistry, language: &'a str) -> Vec<&'a str> {
+        registry.by_language(language).map(|e| e.name.as_str()).collect()
+    }
+
+    #[test]
+    fn normalize_languages() {
+        assert_eq!(normalize_language("en"), "en");
+        assert_eq!(normalize_language("en_US"), "en-us");
+        assert_eq!(normalize_language("PT-br"), "pt-br");
+    }
+
+    #[test]
+    fn guess_languages() {
+        assert_eq!(guess_language("vosk-model-small-en-us-0.15").as_deref(), Some("en-us"));
+        assert_eq!(guess_language("vosk-model-en-us-0.22-lgraph").as_deref(), Some("en-us"));
+        assert_eq!(guess_language("vosk-model-small-de-0.15").as_deref(), Some("de"));
+        assert_eq!(guess_language("vosk-model-ru-0.42").as_deref(), Some("ru"));
+        assert_eq!(guess_language("vosk-model-small-cn-0.22").as_deref(), Some("cn"));
+        assert_eq!(guess_language("Vosk-Model-Small-FR-0.22").as_deref(), Some("fr"));
+        assert_eq!(guess_language("my_models_es_mx").as_deref(), Some("es-mx"));
+        // A version is not a region.
+        assert_eq!(guess_language("vosk-model-small-it-0.22").as_deref(), Some("it"));
+    }
+
+    #[test]
+    fn guess_unknown_languages() {
+        assert_eq!(guess_language("vosk-model-spk-0.4"), None);
+        assert_eq!(guess_language("custom"), None);
+        assert_eq!(guess_language(""), None);
+    }
+
+    #[test]
+    fn match_languages() {
+        let registry = registry(&[
+            ("en-us", Some("en-US")),
+            ("en-in", Some("en_IN")),
+            ("en", Some("en")),
+            ("de", Some("de")),
+            ("spk", None),
+        ]);
+
+        assert_eq!(names(&registry, "en"), vec!["en-us", "en-in", "en"]);
+        assert_eq!(names(&registry, "EN_us"), vec!["en-us"]);
+        assert_eq!(names(&registry, "en-gb"), Vec::<&str>::new());
+        assert_eq!(names(&registry, "d"), Vec::<&str>::new());
+        assert_eq!(names(&registry, "de"), vec!["de"]);
+        assert_eq!(names(&registry, "fr"), Vec::<&str>::new());
+    }
+}
@@ -1,9 +1,15 @@
 use crate::ffi;
-use std::ffi::CString;
-use std::path::{PathBuf};
+use crate::speaker::SpeakerModel;
+use crate::stats::{SessionStats, StatsRecorder};
+use crate::transcription::{TranscriptionPartialResult, TranscriptionResult};
+use serde::de::DeserializeOwned;
+use std::ffi::{CStr, CString};
+use std::os::raw::c_char;
+use std::sync::Arc;
+use std::time::Instant;
 
 pub struct VoskSessionConfigBuilder {
-    spk_root: Option<CString>,
+    spk_model: Option<Arc<SpeakerModel>>,
     grammar: Option<CString>,
     freq: f32,
 }
@@ -11,14 +17,16 @@ pub struct VoskSessionConfigBuilder {
 impl VoskSessionConfigBuilder {
     fn new() -> Self {
         VoskSessionConfigBuilder {
-            spk_root: None,
+            spk_model: None,
             grammar: None,
             freq: 16000.0,
         }
     }
 
-    pub fn spk_root<P: Into<PathBuf>>(&mut self, root: P) -> &mut Self {
-        self.spk_root = Some(unsafe { CString::from_vec_unchecked(root.into().to_string_lossy().as_bytes().to_vec()) });
+    /// Adds x-vectors to the results. Vosk cannot combine a speaker model
+    /// with a grammar, so a grammar set alongside it is ignored.
+    pub fn speaker_model(&mut self, model: Arc<SpeakerModel>) -> &mut Self {
+        self.spk_model = Some(model);
         self
     }
 
@@ -34,15 +42,16 @@ impl VoskSessionConfigBuilder {
 
     pub fn finish(&mut self) -> VoskSessionConfig {
         VoskSessionConfig {
-            spk_root: core::mem::take(&mut self.spk_root),
+            spk_model: core::mem::take(&mut self.spk_model),
             grammar: core::mem::take(&mut self.grammar),
             freq: self.freq,
         }
     }
 }
 
+#[derive(Clone)]
 pub struct VoskSessionConfig {
-    spk_root: Option<CString>,
+    spk_model: Option<Arc<SpeakerModel>>,
     grammar: Option<CString>,
     freq: f32,
 }
@@ -50,7 +59,7 @@ pub struct VoskSessionConfig {
 impl Default for VoskSessionConfig {
     fn default() -> Self {
         Self {
-            spk_root: None,
+            spk_model: None,
             grammar: None,
             freq: 16000.0
         }
@@ -62,9 +71,10 @@ impl VoskSessionConfig {
         VoskSessionConfigBuilder::new()
     }
 
+    /// See `VoskSessionConfigBuilder::speaker_model`.
     #[inline]
-    pub fn set_spk_root(&mut self, root: CString) {
-        self.spk_root = Some(root);
+    pub fn set_speaker_model(&mut self, model: Arc<SpeakerModel>) {
+        self.spk_model = Some(model);
     }
 
     #[inline]
@@ -79,27 +89,153 @@ impl VoskSessionConfig {
 }
 
 pub struct VoskSession {
-    pub(crate) inner: *mut ffi::VoskRecognizer
+    pub(crate) inner: *mut ffi::VoskRecognizer,
+    _spk_model: Option<Arc<SpeakerModel>>,
+    freq: f32,
+    stats: StatsRecorder,
+    #[cfg(feature = "tracing")]
+    span: tracing::Span,
 }
 
 impl VoskSession {
     pub(crate) fn new(model: *const ffi::VoskModel, cfg: VoskSessionConfig) -> Self {
-        if let Some(_cfg) = &cfg.spk_root {
-            unimplemented!()
-            // VoskSession {
-            //     inner: ffi::KaldiRecognizer::new1(model as *mut ffi::Model,  cfg.freq)
-            // }
-        } else if let Some(_grammar) = &cfg.grammar {
-            unimplemented!()
-            // VoskSession {
-            //     inner: unsafe { ffi::KaldiRecognizer::new2(model as *mut ffi::Model, cfg.freq, grammar.as_c_str().as_ptr()) }
-            // }
+        let model = model as *mut ffi::VoskModel;
+
+        #[cfg(feature = "tracing")]
+        let span = tracing::info_span!(
+            "vosk_session",
+            freq = cfg.freq as f64,
+            grammar = cfg.grammar.is_some(),
+            speaker = cfg.spk_model.is_some()
+        );
+        #[cfg(feature = "tracing")]
+        let started = Instant::now();
+
+        let (inner, spk_model) = if let Some(spk_model) = cfg.spk_model.filter(|m| !m.inner.is_null()) {
+            (unsafe { ffi::vosk_recognizer_new_spk(model, spk_model.inner, cfg.freq) }, Some(spk_model))
+        } else if let Some(grammar) = &cfg.grammar {
+            (unsafe { ffi::vosk_recognizer_new_grm(model, cfg.freq, grammar.as_c_str().as_ptr()) }, None)
         } else {
-            VoskSession {
-                inner: unsafe { ffi::vosk_recognizer_new(model as *mut ffi::VoskModel, cfg.freq) }
-            }
+            (unsafe { ffi::vosk_recognizer_new(model, cfg.freq) }, None)
+        };
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            parent: &span,
+            elapsed_us = started.elapsed().as_micros() as u64,
+            created = !inner.is_null(),
+            "session created"
+        );
+
+        VoskSession {
+            inner,
+            _spk_model: spk_model,
+            freq: cfg.freq,
+            stats: StatsRecorder::default(),
+            #[cfg(feature = "tracing")]
+            span,
         }
     }
+
+    /// A snapshot of the counters since the session was created or the last
+    /// `reset_stats`.
+    #[inline]
+    pub fn stats(&self) -> SessionStats {
+        self.stats.stats()
+    }
+
+    pub fn reset_stats(&mut self) {
+        self.stats.reset();
+    }
+
+    pub fn accept_waveform(&mut self, data: &[i16]) -> bool {
+        #[cfg(feature = "tracing")]
+        let span = tracing::trace_span!(parent: &self.span, "vosk_accept", samples = data.len());
+        #[cfg(feature = "tracing")]
+        let _enter = span.enter();
+
+        let started = Instant::now();
+        let completed = unsafe { ffi::vosk_recognizer_accept_waveform_s(self.inner, data.as_ptr(), data.len() as _) == 1 };
+        let finished = Instant::now();
+
+        #[cfg(feature = "tracing")]
+        tracing::trace!(
+            samples = data.len(),
+            duration_us = (finished - started).as_micros() as u64,
+            finalized = completed,
+            "accepted audio"
+        );
+
+        self.stats.accept(data.len(), self.freq, started, finished);
+
+        completed
+    }
+
+    pub fn result(&mut self) -> TranscriptionResult {
+        let result: TranscriptionResult = self.fetch("result", |inner| unsafe { ffi::vosk_recognizer_result(inner) });
+
+        self.stats.result(&result);
+
+        result
+    }
+
+    pub fn partial_result(&mut self) -> TranscriptionPartialResult {
+        let partial: TranscriptionPartialResult =
+            self.fetch("partial", |inner| unsafe { ffi::vosk_recognizer_partial_result(inner) });
+
+        self.stats.partial(&partial.partial);
+
+        partial
+    }
+
+    /// Flushes the buffered audio; the session keeps accepting audio after.
+    pub fn final_result(&mut self) -> TranscriptionResult {
+        let result: TranscriptionResult = self.fetch("final", |inner| unsafe { ffi::vosk_recognizer_final_result(inner) });
+
+        self.stats.final_result(&result);
+
+        result
+    }
+
+    /// Flushes the buffered audio and drops its result, without counting it
+    /// as an utterance or a final result.
+    pub(crate) fn discard(&mut self) {
+        unsafe { ffi::vosk_recognizer_final_result(self.inner) };
+        self.stats.discard();
+    }
+
+    /// Calls one of the result functions and parses the JSON it returns.
+    #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+    fn fetch<T, F>(&self, kind: &'static str, call: F) -> T
+    where
+        T: DeserializeOwned,
+        F: FnOnce(*mut ffi::VoskRecognizer) -> *const c_char,
+    {
+        #[cfg(feature = "tracing")]
+        let span = tracing::debug_span!(parent: &self.span, "vosk_result", kind);
+        #[cfg(feature = "tracing")]
+        let _enter = span.enter();
+        #[cfg(feature = "tracing")]
+        let started = Instant::now();
+
+        let json = unsafe { CStr::from_ptr(call(self.inner)) }.to_str().unwrap();
+
+        #[cfg(feature = "tracing")]
+        let parse_started = Instant::now();
+
+        let value = serde_json::from_str(json).unwrap();
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            kind,
+            bytes = json.len(),
+            decode_us = (parse_started - started).as_micros() as u64,
+            parse_us = parse_started.elapsed().as_micros() as u64,
+            "parsed result"
+        );
+
+        value
+    }
 }
 
 impl Drop for VoskSession {
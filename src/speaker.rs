@@ -1,12 +1,21 @@
+use crate::error::VoskError;
 use crate::ffi;
 use std::path::Path;
 use std::ffi::CString;
 
+/// A speaker identification model. Load it once and share it between
+/// sessions with `VoskSessionConfigBuilder::speaker_model`.
 pub struct SpeakerModel {
     pub(crate) inner: *mut ffi::VoskSpkModel
 }
 
+// Like the acoustic model, it is read-only once loaded.
+unsafe impl Send for SpeakerModel {}
+unsafe impl Sync for SpeakerModel {}
+
 impl SpeakerModel {
+    /// Does not report failures: sessions given a model that failed to load
+    /// return no x-vectors. Prefer `try_new`.
     pub fn new(root: &Path) -> Self {
         let root = unsafe { CString::from_vec_unchecked(root.to_string_lossy().as_bytes().to_vec()) };
 
@@ -14,10 +23,79 @@ impl SpeakerModel {
             inner: unsafe { ffi::vosk_spk_model_new(root.as_c_str().as_ptr()) }
         }
     }
+
+    pub fn try_new(root: &Path) -> Result<Self, VoskError> {
+        #[cfg(feature = "dynamic")]
+        ffi::ensure_loaded()?;
+
+        let c_root = unsafe { CString::from_vec_unchecked(root.to_string_lossy().as_bytes().to_vec()) };
+        let inner = unsafe { ffi::vosk_spk_model_new(c_root.as_ptr()) };
+
+        if inner.is_null() {
+            return Err(VoskError::ModelLoad(root.to_path_buf()));
+        }
+
+        Ok(Self { inner })
+    }
 }
 
 impl Drop for SpeakerModel {
     fn drop(&mut self) {
-        unsafe { ffi::vosk_spk_model_free(self.inner) }
+        if !self.inner.is_null() {
+            unsafe { ffi::vosk_spk_model_free(self.inner) }
+        }
+    }
+}
+
+/// Cosine similarity of two x-vectors, in `[-1.0, 1.0]`.
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    let mut dot = 0.0f32;
+    let mut norm_a = 0.0f32;
+    let mut norm_b = 0.0f32;
+
+    for (x, y) in a.iter().zip(b) {
+        dot += x * y;
+        norm_a += x * x;
+        norm_b += y * y;
+    }
+
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
     }
-}
\ No newline at end of file
+
+    dot / (norm_a.sqrt() * norm_b.sqrt())
+}
+
+pub(crate) fn normalize_xvector(xvector: &[f32]) -> Vec<f32> {
+    let norm = xvector.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm == 0.0 {
+        return xvector.to_vec();
+    }
+
+    xvector.iter().map(|x| x / norm).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cosine_similarity_of_vectors() {
+        assert_eq!(cosine_similarity(&[1.0, 0.0], &[2.0, 0.0]), 1.0);
+        assert_eq!(cosine_similarity(&[1.0, 0.0], &[0.0, 3.0]), 0.0);
+        assert_eq!(cosine_similarity(&[1.0, 2.0], &[-1.0, -2.0]), -1.0);
+        assert!((cosine_similarity(&[1.0, 1.0], &[1.0, 0.0]) - std::f32::consts::FRAC_1_SQRT_2).abs() < 1e-6);
+    }
+
+    #[test]
+    fn cosine_similarity_of_zero_vector() {
+        assert_eq!(cosine_similarity(&[0.0, 0.0], &[1.0, 0.0]), 0.0);
+        assert_eq!(cosine_similarity(&[], &[]), 0.0);
+    }
+
+    #[test]
+    fn normalize_to_unit_length() {
+        assert_eq!(normalize_xvector(&[3.0, 4.0]), vec![0.6, 0.8]);
+        assert_eq!(normalize_xvector(&[0.0, 0.0]), vec![0.0, 0.0]);
+    }
+}
@@ -0,0 +1,85 @@
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+use crate::transcription::TranscriptionResult;
+
+/// Counters a `VoskSession` keeps about its own decoding.
+#[derive(Serialize, Debug, Clone, Copy, Default, PartialEq)]
+pub struct SessionStats {
+    /// Seconds of audio fed so far.
+    pub audio_seconds: f64,
+    /// Wall-clock time spent inside accept calls.
+    pub accept_time: Duration,
+    pub accept_calls: u64,
+    /// Non-empty results, including final ones.
+    pub utterances: u64,
+    /// Partial results whose text differed from the previous one.
+    pub partial_updates: u64,
+    /// Time from the end of the last accept call to the last final result.
+    pub final_latency: Option<Duration>,
+}
+
+impl SessionStats {
+    /// Decoding time per second of audio; below 1.0 is faster than real time.
+    pub fn real_time_factor(&self) -> f64 {
+        if self.audio_seconds > 0.0 {
+            self.accept_time.as_secs_f64() / self.audio_seconds
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Keeps `SessionStats` up to date from the calls made on a recognizer.
+#[derive(Debug, Default)]
+pub(crate) struct StatsRecorder {
+    stats: SessionStats,
+    last_accept: Option<Instant>,
+    last_partial: String,
+}
+
+impl StatsRecorder {
+    #[inline]
+    pub(crate) fn stats(&self) -> SessionStats {
+        self.stats
+    }
+
+    pub(crate) fn reset(&mut self) {
+        self.stats = SessionStats::default();
+        self.last_accept = None;
+    }
+
+    pub(crate) fn accept(&mut self, samples: usize, freq: f32, started: Instant, finished: Instant) {
+        self.stats.audio_seconds += samples as f64 / freq as f64;
+        self.stats.accept_time += finished - started;
+        self.stats.accept_calls += 1;
+        self.last_accept = Some(finished);
+    }
+
+    pub(crate) fn partial(&mut self, partial: &str) {
+        if partial != self.last_partial {
+            self.stats.partial_updates += 1;
+            self.last_partial = partial.to_string();
+        }
+    }
+
+    pub(crate) fn result(&mut self, result: &TranscriptionResult) {
+        self.last_partial.clear();
+        if !result.text.is_empty() {
+            self.stats.utterances += 1;
+        }
+    }
+
+    pub(crate) fn final_result(&mut self, result: &TranscriptionResult) {
+        if let Some(last_accept) = self.last_accept {
+            self.stats.final_latency = Some(last_accept.elapsed());
+        }
+        self.result(result);
+    }
+
+    /// The utterance in progress was dropped; nothing is counted for it.
+    pub(crate) fn discard(&mut self) {
+        self.last_partial.clear();
+    }
+}
@@ -8,14 +8,65 @@ pub struct TranscriptionWord {
     pub word: String,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct TranscriptionResult {
     pub text: String,
     #[serde(default = "Vec::new")]
     pub result: Vec<TranscriptionWord>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub spk: Option<Vec<f32>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub spk_frames: Option<u32>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct TranscriptionPartialResult {
     pub partial: String,
-}
\ No newline at end of file
+}
+impl TranscriptionWord {
+    /// One token covering `words`, from the start of the first to the end of
+    /// the last, with their mean confidence.
+    pub fn merge<S: Into<String>>(words: &[TranscriptionWord], word: S) -> Self {
+        let conf = words.iter().map(|w| w.conf).sum::<f32>() / words.len().max(1) as f32;
+
+        TranscriptionWord {
+            conf,
+            start: words.first().map_or(0.0, |w| w.start),
+            end: words.last().map_or(0.0, |w| w.end),
+            word: word.into(),
+        }
+    }
+}
+
+impl TranscriptionResult {
+    /// The timed words, or the words of `text` without timing when the
+    /// recognizer was not asked for them.
+    pub(crate) fn tokens(&self) -> Vec<TranscriptionWord> {
+        if !self.result.is_empty() {
+            return self.result.clone();
+        }
+
+        self.text
+            .split_whitespace()
+            .map(|word| TranscriptionWord {
+                conf: 1.0,
+                end: 0.0,
+                start: 0.0,
+                word: word.to_string(),
+            })
+            .collect()
+    }
+
+    /// A copy with `text` rebuilt from `tokens`, which also replace the words
+    /// if this result had any.
+    pub(crate) fn with_tokens(&self, tokens: Vec<TranscriptionWord>) -> Self {
+        let text = tokens.iter().map(|w| w.word.as_str()).collect::<Vec<_>>().join(" ");
+
+        TranscriptionResult {
+            text,
+            result: if self.result.is_empty() { Vec::new() } else { tokens },
+            spk: self.spk.clone(),
+            spk_frames: self.spk_frames,
+        }
+    }
+}
@@ -0,0 +1,256 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::error::VoskError;
+use crate::transcription::{TranscriptionPartialResult, TranscriptionResult, TranscriptionWord};
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SpokenForms {
+    One(String),
+    Many(Vec<String>),
+}
+
+/// Replaces spoken phrases with domain terms, e.g. "cube ernetes" with
+/// "kubernetes". Matching ignores case; a replaced phrase becomes one word
+/// spanning the timings of the words it replaced, with their mean confidence.
+///
+/// Dictionary files map each term to one or more spoken forms:
+///
+/// ```toml
+/// kubernetes = ["cube ernetes", "cooper netties"]
+/// PostgreSQL = "post gress"
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Vocabulary {
+    entries: Vec<(Vec<String>, String)>,
+}
+
+impl Vocabulary {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reads a dictionary, as TOML if the file ends in `.toml` and as JSON
+    /// otherwise.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, VoskError> {
+        let path = path.as_ref();
+        let data = fs::read_to_string(path)?;
+
+        if path.extension().is_some_and(|ext| ext == "toml") {
+            #[cfg(feature = "toml")]
+            return Self::from_toml(&data);
+            #[cfg(not(feature = "toml"))]
+            return Err(VoskError::Vocabulary("TOML dictionaries need the `toml` feature".to_string()));
+        }
+
+        Self::from_json(&data)
+    }
+
+    pub fn from_json(data: &str) -> Result<Self, VoskError> {
+        Self::from_map(serde_json::from_str(data)?)
+    }
+
+    #[cfg(feature = "toml")]
+    pub fn from_toml(data: &str) -> Result<Self, VoskError> {
+        Self::from_map(toml::from_str(data).map_err(|err| VoskError::Vocabulary(err.to_string()))?)
+    }
+
+    fn from_map(map: BTreeMap<String, SpokenForms>) -> Result<Self, VoskError> {
+        let mut vocabulary = Self::new();
+
+        for (term, spoken) in map {
+            let spoken = match spoken {
+                SpokenForms::One(spoken) => vec![spoken],
+                SpokenForms::Many(spoken) => spoken,
+            };
+
+            for spoken in spoken {
+                if spoken.split_whitespace().next().is_none() {
+                    return Err(VoskError::Vocabulary(format!("empty spoken form for {:?}", term)));
+                }
+                vocabulary.insert(&spoken, &term);
+            }
+        }
+
+        Ok(vocabulary)
+    }
+
+    /// Adds a replacement; an empty `spoken` phrase is ignored.
+    pub fn insert(&mut self, spoken: &str, replacement: &str) {
+        let words: Vec<String> = spoken.split_whitespace().map(str::to_lowercase).collect();
+        if words.is_empty() {
+            return;
+        }
+
+        self.entries.retain(|(w, _)| *w != words);
+        self.entries.push((words, replacement.to_string()));
+        // Longest phrases first, so they win over phrases they start with.
+        self.entries.sort_by_key(|(words, _)| std::cmp::Reverse(words.len()));
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn apply(&self, result: &TranscriptionResult) -> TranscriptionResult {
+        result.with_tokens(self.apply_words(&result.tokens()))
+    }
+
+    pub fn apply_partial(&self, partial: &TranscriptionPartialResult) -> TranscriptionPartialResult {
+        let result = TranscriptionResult {
+            text: partial.partial.clone(),
+            ..Default::default()
+        };
+
+        TranscriptionPartialResult {
+            partial: self.apply(&result).text,
+        }
+    }
+
+    pub fn apply_words(&self, words: &[TranscriptionWord]) -> Vec<TranscriptionWord> {
+        let keys: Vec<String> = words.iter().map(|w| w.word.to_lowercase()).collect();
+
+        let mut out = Vec::with_capacity(words.len());
+        let mut idx = 0;
+
+        while idx < words.len() {
+            let rest = &keys[idx..];
+            let entry = self
+                .entries
+                .iter()
+                .find(|(spoken, _)| rest.len() >= spoken.len() && spoken.iter().zip(rest).all(|(s, w)| s == w));
+
+            match entry {
+                Some((spoken, replacement)) => {
+                    out.push(TranscriptionWord::merge(&words[idx..idx + spoken.len()], replacement.as_str()));
+                    idx += spoken.len();
+                }
+                None => {
+                    out.push(words[idx].clone());
+                    idx += 1;
+                }
+            }
+        }
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn apply(vocabulary: &Vocabulary, text: &str) -> String {
+        let result = TranscriptionResult {
+            text: text.to_string(),
+            ..Default::default()
+        };
+
+        vocabulary.apply(&result).text
+    }
+
+    fn vocabulary() -> Vocabulary {
+        let mut vocabulary = Vocabulary::new();
+        vocabulary.insert("cube ernetes", "kubernetes");
+        vocabulary.insert("post gress", "PostgreSQL");
+        vocabulary.insert("post gress sequel", "PostgreSQL");
+        vocabulary.insert("  ", "nothing");
+
+        vocabulary
+    }
+
+    #[test]
+    fn replaces_multi_word_phrases() {
+        let vocabulary = vocabulary();
+
+        assert_eq!(vocabulary.len(), 3);
+        assert_eq!(apply(&vocabulary, "deploy cube ernetes on post gress sequel"), "deploy kubernetes on PostgreSQL");
+        assert_eq!(apply(&vocabulary, "post gress and post office"), "PostgreSQL and post office");
+        assert_eq!(apply(&vocabulary, "cube"), "cube");
+    }
+
+    #[test]
+    fn ignores_case() {
+        let mut vocabulary = vocabulary();
+        assert_eq!(apply(&vocabulary, "Cube ERNETES"), "kubernetes");
+
+        // Spoken forms differing only in case are the same entry.
+        vocabulary.insert("CUBE Ernetes", "k8s");
+        assert_eq!(vocabulary.len(), 3);
+        assert_eq!(apply(&vocabulary, "cube ernetes"), "k8s");
+    }
+
+    #[test]
+    fn merges_word_timings() {
+        let word = |word: &str, start, end, conf| TranscriptionWord {
+            conf,
+            start,
+            end,
+            word: word.to_string(),
+        };
+
+        let words = vocabulary().apply_words(&[
+            word("on", 0.0, 0.2, 1.0),
+            word("cube", 0.3, 0.5, 0.5),
+            word("ernetes", 0.5, 1.0, 0.7),
+        ]);
+
+        let words: Vec<_> = words.iter().map(|w| (w.word.as_str(), w.start, w.end, w.conf)).collect();
+        assert_eq!(words, vec![("on", 0.0, 0.2, 1.0), ("kubernetes", 0.3, 1.0, 0.6)]);
+    }
+
+    #[test]
+    fn partial_results() {
+        let partial = TranscriptionPartialResult {
+            partial: "start post gress".to_string(),
+        };
+
+        assert_eq!(vocabulary().apply_partial(&partial).partial, "start PostgreSQL");
+    }
+
+    #[test]
+    fn json_dictionary() {
+        let json = r#"{"kubernetes": ["cube ernetes", "cooper netties"], "k9s": "canines"}"#;
+        let vocabulary = Vocabulary::from_json(json).unwrap();
+
+        assert_eq!(vocabulary.len(), 3);
+        assert_eq!(apply(&vocabulary, "cooper netties canines"), "kubernetes k9s");
+
+        assert!(matches!(Vocabulary::from_json(r#"{"empty": " "}"#), Err(VoskError::Vocabulary(_))));
+        assert!(Vocabulary::from_json("[]").is_err());
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn toml_dictionary() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("terms.toml");
+        fs::write(&path, "kubernetes = [\"cube ernetes\", \"cooper netties\"]\nPostgreSQL = \"post gress\"\n").unwrap();
+
+        let vocabulary = Vocabulary::load(&path).unwrap();
+        assert_eq!(vocabulary.len(), 3);
+        assert_eq!(apply(&vocabulary, "Cooper Netties and post gress"), "kubernetes and PostgreSQL");
+
+        assert!(matches!(Vocabulary::from_toml("kubernetes = 1"), Err(VoskError::Vocabulary(_))));
+    }
+
+    #[cfg(not(feature = "toml"))]
+    #[test]
+    fn toml_needs_feature() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("terms.toml");
+        fs::write(&path, "kubernetes = \"cube ernetes\"\n").unwrap();
+
+        assert!(matches!(Vocabulary::load(&path), Err(VoskError::Vocabulary(_))));
+    }
+}
@@ -0,0 +1,295 @@
+use std::fs;
+use std::io::{BufReader, BufWriter};
+use std::path::Path;
+
+use serde::{Serialize, Deserialize};
+
+use crate::error::VoskError;
+use crate::speaker::{cosine_similarity, normalize_xvector};
+use crate::transcription::TranscriptionResult;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Voiceprint {
+    pub name: String,
+    pub xvector: Vec<f32>,
+    pub utterances: usize,
+}
+
+impl Voiceprint {
+    /// Averages the length-normalized x-vectors of the enrollment utterances.
+    pub fn from_xvectors<'a, N, I>(name: N, xvectors: I) -> Option<Self>
+    where
+        N: Into<String>,
+        I: IntoIterator<Item = &'a [f32]>,
+    {
+        let mut sum: Vec<f32> = Vec::new();
+        let mut utterances = 0;
+
+        for xvector in xvectors {
+            if xvector.is_empty() {
+                continue;
+            }
+
+            let xvector = normalize_xvector(xvector);
+            if sum.is_empty() {
+                sum = xvector;
+            } else if sum.len() == xvector.len() {
+                for (a, b) in sum.iter_mut().zip(xvector) {
+                    *a += b;
+                }
+            } else {
+                continue;
+            }
+
+            utterances += 1;
+        }
+
+        if utterances == 0 {
+            return None;
+        }
+
+        Some(Self {
+            name: name.into(),
+            xvector: sum.into_iter().map(|x| x / utterances as f32).collect(),
+            utterances,
+        })
+    }
+
+    /// Builds a voiceprint from results of a session created with a speaker model.
+    pub fn from_results<N: Into<String>>(name: N, results: &[TranscriptionResult]) -> Option<Self> {
+        Self::from_xvectors(name, results.iter().filter_map(|r| r.spk.as_deref()))
+    }
+
+    #[inline]
+    pub fn similarity(&self, xvector: &[f32]) -> f32 {
+        cosine_similarity(&self.xvector, xvector)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Verification {
+    Accepted(f32),
+    Rejected(f32),
+    NotEnrolled,
+}
+
+impl Verification {
+    #[inline]
+    pub fn is_accepted(&self) -> bool {
+        matches!(self, Verification::Accepted(_))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpeakerMatch {
+    pub name: String,
+    pub similarity: f32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct VoiceprintStore {
+    threshold: f32,
+    voiceprints: Vec<Voiceprint>,
+}
+
+impl Default for VoiceprintStore {
+    fn default() -> Self {
+        Self {
+            threshold: 0.6,
+            voiceprints: Vec::new(),
+        }
+    }
+}
+
+impl VoiceprintStore {
+    pub fn new(threshold: f32) -> Self {
+        Self {
+            threshold,
+            voiceprints: Vec::new(),
+        }
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, VoskError> {
+        let file = fs::File::open(path)?;
+
+        Ok(serde_json::from_reader(BufReader::new(file))?)
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), VoskError> {
+        let file = fs::File::create(path)?;
+        serde_json::to_writer_pretty(BufWriter::new(file), self)?;
+
+        Ok(())
+    }
+
+    #[inline]
+    pub fn threshold(&self) -> f32 {
+        self.threshold
+    }
+
+    #[inline]
+    pub fn set_threshold(&mut self, threshold: f32) {
+        self.threshold = threshold;
+    }
+
+    #[inline]
+    pub fn voiceprints(&self) -> &[Voiceprint] {
+        &self.voiceprints
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Voiceprint> {
+        self.voiceprints.iter().find(|v| v.name == name)
+    }
+
+    /// Adds `voiceprint`, replacing any voiceprint enrolled under the same name.
+    pub fn insert(&mut self, voiceprint: Voiceprint) {
+        self.remove(&voiceprint.name);
+        self.voiceprints.push(voiceprint);
+    }
+
+    /// Enrolls `name` from the x-vectors of `results`. Returns `false` if none
+    /// of the results carry an x-vector.
+    pub fn enroll<N: Into<String>>(&mut self, name: N, results: &[TranscriptionResult]) -> bool {
+        match Voiceprint::from_results(name, results) {
+            Some(voiceprint) => {
+                self.insert(voiceprint);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<Voiceprint> {
+        let idx = self.voiceprints.iter().position(|v| v.name == name)?;
+
+        Some(self.voiceprints.remove(idx))
+    }
+
+    pub fn verify(&self, name: &str, xvector: &[f32]) -> Verification {
+        match self.get(name) {
+            Some(voiceprint) => {
+                let similarity = voiceprint.similarity(xvector);
+                if similarity >= self.threshold {
+                    Verification::Accepted(similarity)
+                } else {
+                    Verification::Rejected(similarity)
+                }
+            }
+            None => Verification::NotEnrolled,
+        }
+    }
+
+    /// Returns the closest enrolled speaker, if it clears the threshold.
+    pub fn identify(&self, xvector: &[f32]) -> Option<SpeakerMatch> {
+        self.voiceprints
+            .iter()
+            .map(|v| SpeakerMatch {
+                name: v.name.clone(),
+                similarity: v.similarity(xvector),
+            })
+            .filter(|m| m.similarity >= self.threshold)
+            .max_by(|a, b| a.similarity.partial_cmp(&b.similarity).unwrap_or(std::cmp::Ordering::Equal))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(spk: Option<Vec<f32>>) -> TranscriptionResult {
+        TranscriptionResult {
+            text: "hello".to_string(),
+            spk,
+            ..Default::default()
+        }
+    }
+
+    fn store() -> VoiceprintStore {
+        let mut store = VoiceprintStore::new(0.8);
+        store.insert(Voiceprint::from_xvectors("alice", [&[1.0, 0.0, 0.0][..]]).unwrap());
+        store.insert(Voiceprint::from_xvectors("bob", [&[0.0, 1.0, 0.0][..]]).unwrap());
+        store.insert(Voiceprint::from_xvectors("carol", [&[0.6, 0.8, 0.0][..]]).unwrap());
+
+        store
+    }
+
+    #[test]
+    fn enrollment_averages_normalized_xvectors() {
+        let voiceprint = Voiceprint::from_xvectors("alice", [&[2.0, 0.0][..], &[0.0, 4.0][..]]).unwrap();
+
+        assert_eq!(voiceprint.xvector, vec![0.5, 0.5]);
+        assert_eq!(voiceprint.utterances, 2);
+    }
+
+    #[test]
+    fn enrollment_skips_unusable_xvectors() {
+        let voiceprint = Voiceprint::from_xvectors(
+            "alice",
+            [&[3.0, 4.0][..], &[][..], &[1.0, 2.0, 3.0][..], &[0.0, 2.0][..]],
+        )
+        .unwrap();
+
+        assert_eq!(voiceprint.xvector, vec![0.3, 0.9]);
+        assert_eq!(voiceprint.utterances, 2);
+        assert!(Voiceprint::from_xvectors("nobody", [&[][..]]).is_none());
+    }
+
+    #[test]
+    fn enroll_from_results() {
+        let mut store = VoiceprintStore::default();
+
+        assert!(!store.enroll("alice", &[result(None)]));
+        assert!(store.voiceprints().is_empty());
+
+        assert!(store.enroll("alice", &[result(Some(vec![1.0, 0.0])), result(None)]));
+        assert!(store.enroll("alice", &[result(Some(vec![0.0, 1.0]))]));
+
+        // Enrolling again replaces the voiceprint.
+        assert_eq!(store.voiceprints().len(), 1);
+        assert_eq!(store.get("alice").unwrap().xvector, vec![0.0, 1.0]);
+        assert_eq!(store.get("alice").unwrap().utterances, 1);
+    }
+
+    #[test]
+    fn verify_against_threshold() {
+        let mut store = store();
+
+        assert_eq!(store.verify("alice", &[2.0, 0.0, 0.0]), Verification::Accepted(1.0));
+        assert_eq!(store.verify("carol", &[1.0, 0.0, 0.0]), Verification::Rejected(0.6));
+        assert_eq!(store.verify("dave", &[1.0, 0.0, 0.0]), Verification::NotEnrolled);
+
+        store.set_threshold(0.6);
+        assert!(store.verify("carol", &[1.0, 0.0, 0.0]).is_accepted());
+    }
+
+    #[test]
+    fn identify_closest_speaker() {
+        let mut store = store();
+
+        let found = store.identify(&[0.5, 0.8, 0.0]).unwrap();
+        assert_eq!(found.name, "carol");
+        assert!(found.similarity > 0.99);
+
+        assert_eq!(store.identify(&[0.0, 0.1, 1.0]), None);
+        assert_eq!(store.identify(&[0.0, 0.0, 0.0]), None);
+
+        store.set_threshold(0.0);
+        assert_eq!(store.identify(&[0.0, 3.0, 0.1]).unwrap().name, "bob");
+
+        store.remove("bob");
+        assert_eq!(store.identify(&[0.0, 3.0, 0.1]).unwrap().name, "carol");
+    }
+
+    #[test]
+    fn save_and_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("voiceprints.json");
+        store().save(&path).unwrap();
+
+        let loaded = VoiceprintStore::load(&path).unwrap();
+        assert_eq!(loaded.threshold(), 0.8);
+        let names: Vec<&str> = loaded.voiceprints().iter().map(|v| v.name.as_str()).collect();
+        assert_eq!(names, vec!["alice", "bob", "carol"]);
+        assert_eq!(loaded.get("carol").unwrap().xvector, vec![0.6, 0.8, 0.0]);
+    }
+}
@@ -0,0 +1,428 @@
+use std::fmt;
+use std::ops::AddAssign;
+
+use serde::{Deserialize, Serialize};
+
+use crate::transcription::TranscriptionResult;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum EditOp {
+    Match,
+    Substitution,
+    Insertion,
+    Deletion,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ErrorCounts {
+    pub substitutions: usize,
+    pub insertions: usize,
+    pub deletions: usize,
+    pub reference_len: usize,
+}
+
+impl ErrorCounts {
+    /// Minimum edit counts between two token sequences, in linear memory.
+    pub fn compute<T: PartialEq>(reference: &[T], hypothesis: &[T]) -> Self {
+        let mut prev: Vec<ErrorCounts> = (0..=hypothesis.len())
+            .map(|j| ErrorCounts {
+                insertions: j,
+                ..Default::default()
+            })
+            .collect();
+        let mut cur = prev.clone();
+
+        for (i, r) in reference.iter().enumerate() {
+            cur[0] = ErrorCounts {
+                deletions: i + 1,
+                ..Default::default()
+            };
+
+            for (j, h) in hypothesis.iter().enumerate() {
+                let mut diag = prev[j];
+                if r != h {
+                    diag.substitutions += 1;
+                }
+                let mut del = prev[j + 1];
+                del.deletions += 1;
+                let mut ins = cur[j];
+                ins.insertions += 1;
+
+                cur[j + 1] = [diag, del, ins].iter().copied().min_by_key(ErrorCounts::errors).unwrap();
+            }
+
+            std::mem::swap(&mut prev, &mut cur);
+        }
+
+        ErrorCounts {
+            reference_len: reference.len(),
+            ..prev[hypothesis.len()]
+        }
+    }
+
+    #[inline]
+    pub fn errors(&self) -> usize {
+        self.substitutions + self.insertions + self.deletions
+    }
+
+    /// Errors per reference token; an empty reference counts as one token.
+    pub fn rate(&self) -> f32 {
+        self.errors() as f32 / self.reference_len.max(1) as f32
+    }
+}
+
+impl AddAssign for ErrorCounts {
+    fn add_assign(&mut self, other: Self) {
+        self.substitutions += other.substitutions;
+        self.insertions += other.insertions;
+        self.deletions += other.deletions;
+        self.reference_len += other.reference_len;
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AlignedPair {
+    pub op: EditOp,
+    pub reference: Option<String>,
+    pub hypothesis: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct Alignment {
+    pub pairs: Vec<AlignedPair>,
+}
+
+impl Alignment {
+    /// Aligns the normalized words of both texts.
+    pub fn words(reference: &str, hypothesis: &str) -> Self {
+        let reference = normalize(reference);
+        let hypothesis = normalize(hypothesis);
+        let reference: Vec<&str> = reference.split(' ').filter(|w| !w.is_empty()).collect();
+        let hypothesis: Vec<&str> = hypothesis.split(' ').filter(|w| !w.is_empty()).collect();
+
+        Self::align(&reference, &hypothesis)
+    }
+
+    /// Levenshtein alignment, preferring matches and substitutions over
+    /// deletions over insertions when several paths are equally short.
+    pub fn align<T: AsRef<str> + PartialEq>(reference: &[T], hypothesis: &[T]) -> Self {
+        let (ops, _) = edit_table(reference, hypothesis, false);
+
+        let pairs = backtrace(&ops, hypothesis.len() + 1, reference.len(), hypothesis.len())
+            .into_iter()
+            .map(|(op, r, h)| AlignedPair {
+                op,
+                reference: r.map(|r| reference[r].as_ref().to_string()),
+                hypothesis: h.map(|h| hypothesis[h].as_ref().to_string()),
+            })
+            .collect();
+
+        Self { pairs }
+    }
+
+    pub fn counts(&self) -> ErrorCounts {
+        let mut counts = ErrorCounts::default();
+
+        for pair in &self.pairs {
+            match pair.op {
+                EditOp::Match => {}
+                EditOp::Substitution => counts.substitutions += 1,
+                EditOp::Insertion => counts.insertions += 1,
+                EditOp::Deletion => counts.deletions += 1,
+            }
+            if pair.reference.is_some() {
+                counts.reference_len += 1;
+            }
+        }
+
+        counts
+    }
+}
+
+/// The operation ending the best path to each cell of the Levenshtein table,
+/// row-major with a row per reference prefix, and the cost of aligning each
+/// reference prefix with the whole hypothesis. With `free_start`, reference
+/// words before the first hypothesis word are skipped at no cost.
+pub(crate) fn edit_table<T: PartialEq>(reference: &[T], hypothesis: &[T], free_start: bool) -> (Vec<EditOp>, Vec<usize>) {
+    let width = hypothesis.len() + 1;
+    let mut ops = vec![EditOp::Match; (reference.len() + 1) * width];
+    let mut prev: Vec<usize> = (0..width).collect();
+    let mut cur = vec![0; width];
+    let mut costs = Vec::with_capacity(reference.len() + 1);
+    costs.push(hypothesis.len());
+
+    for op in ops.iter_mut().take(width).skip(1) {
+        *op = EditOp::Insertion;
+    }
+
+    for (i, r) in reference.iter().enumerate() {
+        cur[0] = if free_start { 0 } else { i + 1 };
+        ops[(i + 1) * width] = EditOp::Deletion;
+
+        for (j, h) in hypothesis.iter().enumerate() {
+            let (diag_op, diag) = if r == h {
+                (EditOp::Match, prev[j])
+            } else {
+                (EditOp::Substitution, prev[j] + 1)
+            };
+
+            let (op, cost) = [(diag_op, diag), (EditOp::Deletion, prev[j + 1] + 1), (EditOp::Insertion, cur[j] + 1)]
+                .iter()
+                .copied()
+                .min_by_key(|(_, cost)| *cost)
+                .unwrap();
+
+            cur[j + 1] = cost;
+            ops[(i + 1) * width + j + 1] = op;
+        }
+
+        std::mem::swap(&mut prev, &mut cur);
+        costs.push(prev[hypothesis.len()]);
+    }
+
+    (ops, costs)
+}
+
+/// Follows `ops` back from the cell `(i, j)` to the start, returning each
+/// operation with the reference and hypothesis index it consumes, in order.
+pub(crate) fn backtrace(ops: &[EditOp], width: usize, mut i: usize, mut j: usize) -> Vec<(EditOp, Option<usize>, Option<usize>)> {
+    let mut path = Vec::new();
+
+    while i > 0 || j > 0 {
+        let op = ops[i * width + j];
+        let step = match op {
+            EditOp::Match | EditOp::Substitution => {
+                i -= 1;
+                j -= 1;
+                (op, Some(i), Some(j))
+            }
+            EditOp::Deletion => {
+                i -= 1;
+                (op, Some(i), None)
+            }
+            EditOp::Insertion => {
+                j -= 1;
+                (op, None, Some(j))
+            }
+        };
+        path.push(step);
+    }
+
+    path.reverse();
+
+    path
+}
+
+/// Column-aligned `REF:`, `HYP:` and `EVAL:` lines, gaps shown as `***`.
+impl fmt::Display for Alignment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut lines = [String::from("REF: "), String::from("HYP: "), String::from("EVAL:")];
+
+        for pair in &self.pairs {
+            let reference = pair.reference.as_deref().unwrap_or("***");
+            let hypothesis = pair.hypothesis.as_deref().unwrap_or("***");
+            let eval = match pair.op {
+                EditOp::Match => "",
+                EditOp::Substitution => "S",
+                EditOp::Insertion => "I",
+                EditOp::Deletion => "D",
+            };
+            let width = reference.chars().count().max(hypothesis.chars().count());
+
+            for (line, cell) in lines.iter_mut().zip(&[reference, hypothesis, eval]) {
+                line.push(' ');
+                line.push_str(cell);
+                line.push_str(&" ".repeat(width - cell.chars().count()));
+            }
+        }
+
+        for line in &lines {
+            writeln!(f, "{}", line.trim_end())?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Word and character error rates of one hypothesis against its reference.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Evaluation {
+    pub words: ErrorCounts,
+    pub chars: ErrorCounts,
+    pub alignment: Alignment,
+}
+
+impl Evaluation {
+    pub fn new(reference: &str, hypothesis: &str) -> Self {
+        let alignment = Alignment::words(reference, hypothesis);
+        let reference: Vec<char> = normalize(reference).chars().collect();
+        let hypothesis: Vec<char> = normalize(hypothesis).chars().collect();
+
+        Self {
+            words: alignment.counts(),
+            chars: ErrorCounts::compute(&reference, &hypothesis),
+            alignment,
+        }
+    }
+
+    /// Scores the utterances of one recording, joined in order.
+    pub fn from_results(reference: &str, results: &[TranscriptionResult]) -> Self {
+        let hypothesis: Vec<&str> = results.iter().map(|r| r.text.as_str()).collect();
+
+        Self::new(reference, &hypothesis.join(" "))
+    }
+
+    #[inline]
+    pub fn wer(&self) -> f32 {
+        self.words.rate()
+    }
+
+    #[inline]
+    pub fn cer(&self) -> f32 {
+        self.chars.rate()
+    }
+}
+
+/// Error counts summed over many evaluations, so the corpus rates weigh each
+/// utterance by its length.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+pub struct CorpusEvaluation {
+    pub utterances: usize,
+    pub utterances_with_errors: usize,
+    pub words: ErrorCounts,
+    pub chars: ErrorCounts,
+}
+
+impl CorpusEvaluation {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, evaluation: &Evaluation) {
+        self.utterances += 1;
+        if evaluation.words.errors() > 0 {
+            self.utterances_with_errors += 1;
+        }
+        self.words += evaluation.words;
+        self.chars += evaluation.chars;
+    }
+
+    #[inline]
+    pub fn wer(&self) -> f32 {
+        self.words.rate()
+    }
+
+    #[inline]
+    pub fn cer(&self) -> f32 {
+        self.chars.rate()
+    }
+
+    pub fn sentence_error_rate(&self) -> f32 {
+        self.utterances_with_errors as f32 / self.utterances.max(1) as f32
+    }
+}
+
+impl<'a> Extend<&'a Evaluation> for CorpusEvaluation {
+    fn extend<I: IntoIterator<Item = &'a Evaluation>>(&mut self, iter: I) {
+        for evaluation in iter {
+            self.add(evaluation);
+        }
+    }
+}
+
+/// Lowercases and replaces everything but letters, digits and apostrophes
+/// with single spaces, matching the shape of Vosk output.
+pub fn normalize(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+
+    for word in text
+        .split(|c: char| !(c.is_alphanumeric() || c == '\''))
+        .filter(|w| !w.is_empty())
+    {
+        if !out.is_empty() {
+            out.push(' ');
+        }
+        out.extend(word.chars().flat_map(char::to_lowercase));
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn counts(substitutions: usize, insertions: usize, deletions: usize, reference_len: usize) -> ErrorCounts {
+        ErrorCounts {
+            substitutions,
+            insertions,
+            deletions,
+            reference_len,
+        }
+    }
+
+    #[test]
+    fn compute_counts_each_kind() {
+        let words = |text: &str| text.split(' ').map(str::to_string).collect::<Vec<_>>();
+
+        assert_eq!(ErrorCounts::compute(&words("a b c"), &words("a b c")), counts(0, 0, 0, 3));
+        assert_eq!(ErrorCounts::compute(&words("a b c"), &words("a x c")), counts(1, 0, 0, 3));
+        assert_eq!(ErrorCounts::compute(&words("a b c"), &words("a b x c")), counts(0, 1, 0, 3));
+        assert_eq!(ErrorCounts::compute(&words("a b c"), &words("a c")), counts(0, 0, 1, 3));
+        assert_eq!(ErrorCounts::compute::<char>(&[], &['a', 'b']), counts(0, 2, 0, 0));
+    }
+
+    #[test]
+    fn rates() {
+        assert_eq!(counts(1, 1, 0, 4).rate(), 0.5);
+        // An empty reference counts as one token.
+        assert_eq!(counts(0, 2, 0, 0).rate(), 2.0);
+        assert_eq!(ErrorCounts::default().rate(), 0.0);
+    }
+
+    #[test]
+    fn align_prefers_substitutions_then_deletions() {
+        let ops = |reference: &[&str], hypothesis: &[&str]| -> Vec<EditOp> {
+            Alignment::align(reference, hypothesis).pairs.iter().map(|p| p.op).collect()
+        };
+
+        // Two substitutions rather than a deletion and an insertion.
+        assert_eq!(ops(&["the", "mat"], &["mat", "now"]), [EditOp::Substitution, EditOp::Substitution]);
+        assert_eq!(ops(&["a", "b"], &["b"]), [EditOp::Deletion, EditOp::Match]);
+        assert_eq!(ops(&["a"], &["x", "a"]), [EditOp::Insertion, EditOp::Match]);
+        assert_eq!(ops(&[], &[]), []);
+    }
+
+    #[test]
+    fn display_lines_up_columns() {
+        let alignment = Alignment::words("The cat sat down", "the bat sat down here");
+
+        assert_eq!(
+            alignment.to_string(),
+            "REF:  the cat sat down ***\nHYP:  the bat sat down here\nEVAL:     S            I\n"
+        );
+        assert_eq!(alignment.counts(), counts(1, 1, 0, 4));
+    }
+
+    #[test]
+    fn evaluation_rates() {
+        let evaluation = Evaluation::new("Hello, world!", "hello word");
+        assert_eq!(evaluation.words, counts(1, 0, 0, 2));
+        assert_eq!(evaluation.wer(), 0.5);
+        // "hello world" to "hello word" is one deletion in 11 characters.
+        assert_eq!(evaluation.chars, counts(0, 0, 1, 11));
+
+        let mut corpus = CorpusEvaluation::new();
+        corpus.extend([&evaluation, &Evaluation::new("a b c d", "a b c d")]);
+        assert_eq!(corpus.words, counts(1, 0, 0, 6));
+        assert!((corpus.wer() - 1.0 / 6.0).abs() < 1e-6);
+        assert_eq!(corpus.sentence_error_rate(), 0.5);
+    }
+
+    #[test]
+    fn normalize_matches_vosk_output() {
+        assert_eq!(normalize("  It's  a well-known FACT. "), "it's a well known fact");
+        assert_eq!(normalize("..."), "");
+    }
+}